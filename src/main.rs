@@ -1,7 +1,18 @@
+mod util;
+mod tools;
 mod y22;
 mod y23;
 
 fn main() {
+    let args = Vec::from_iter(std::env::args().skip(1));
+    if !args.is_empty() {
+        if let Err(e) = tools::command(&args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     #[cfg(debug_assertions)]
     std::panic::set_hook(Box::new(|info| {
         println!("PANIC! {:?}", info);
@@ -13,13 +24,19 @@ fn main() {
         core::hint::black_box(i);
     }
 
+    y22::d19::main();
+    y22::d23::main();
+
     y23::d01::main();
+    y23::d02::main();
+    y23::d03::main();
+    y23::d04::main();
     y23::d07::main();
     y23::d08::main();
     y23::d09::main();
     y23::d10::main();
     y23::d11::main();
-    //y23::d12::main();
+    y23::d12::main();
     y23::d13::main();
     y23::d14::main();
 }
@@ -0,0 +1,169 @@
+// downloads `dNN-prod.txt` and the examples `dNN-test.txt`, `dNN-test-2.txt`, ...
+// existing files are never downloaded again.
+// empty ones don't count, `new` creates those as placeholders.
+// the examples are the first `<pre><code>` block of each part's description.
+// how many parts the puzzle page had is kept in `target/aoc/`.
+
+use super::{Config, Day};
+use super::http::Client;
+use super::submit::History;
+
+
+pub fn fetch(config: &Config, day: Day) -> Result<(), String> {
+    let dir = config.year_dir(day);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let client = Client::new(config);
+
+    let prod = config.input_path(day, "prod");
+    if is_cached(&prod) {
+        println!("{} is cached", prod.display());
+    }
+    else {
+        let input = client.get(&format!("/{}/day/{}/input", day.year, day.day))?;
+        write(&prod, &input)?;
+    }
+
+    // part 2's example only shows up once part 1 is solved.
+    // so the page is fetched again if it only had part 1 last time,
+    // and part 1 has been solved since.
+    let parts_path = config.state_path(day, "parts");
+    let parts = std::fs::read_to_string(&parts_path).ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    let solved = History::load(config, day).is_ok_and(|h| h.correct(1).is_some());
+    if parts >= 2 || (parts == 1 && !solved) {
+        println!("examples are cached");
+        return Ok(());
+    }
+
+    let page = client.get(&format!("/{}/day/{}", day.year, day.day))?;
+    let examples = examples(&page);
+    if examples.is_empty() {
+        println!("no examples on the puzzle page");
+    }
+    for (i, example) in examples.iter().enumerate() {
+        let path = config.input_path(day, &test_name(i + 1));
+        if !is_cached(&path) {
+            write(&path, example)?;
+        }
+    }
+
+    let parts = page.matches("<article").count();
+    if let Some(dir) = parts_path.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    std::fs::write(&parts_path, parts.to_string()).map_err(|e| format!("{}: {e}", parts_path.display()))?;
+
+    return Ok(());
+}
+
+fn is_cached(path: &std::path::Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+fn test_name(index: usize) -> String {
+    if index == 1 { "test".into() } else { format!("test-{index}") }
+}
+
+fn write(path: &std::path::Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|e| format!("{}: {e}", path.display()))?;
+    println!("wrote {} ({} bytes)", path.display(), content.len());
+    Ok(())
+}
+
+
+// first code block of each `<article>`, without tags & entities.
+// identical blocks are only kept once.
+pub fn examples(page: &str) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    for article in page.split("<article").skip(1) {
+        let article = article.split("</article>").next().unwrap();
+
+        let Some((_, code)) = article.split_once("<pre><code>") else { continue };
+        let Some((code, _)) = code.split_once("</code></pre>") else { continue };
+
+        let code = unescape(&strip_tags(code));
+        if !result.contains(&code) {
+            result.push(code);
+        }
+    }
+    return result;
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        result.push_str(&rest[..open]);
+        rest = match rest[open..].find('>') {
+            Some(close) => &rest[open + close + 1..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    return result;
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use super::super::stand_in::{self, StandIn};
+
+    const PART_1: &str = "<article><p>a</p><pre><code>1 &lt; 2\n</code></pre><pre><code>x</code></pre></article>";
+    // part 2 uses the same example.
+    const PART_2: &str = "<article><pre><code>1 &lt; 2\n</code></pre></article>";
+
+    #[test]
+    fn fetch_once() {
+        let unlocked = Arc::new(AtomicBool::new(false));
+        let server = StandIn::start({
+            let unlocked = unlocked.clone();
+            move |request| match request.path.as_str() {
+                "/2023/day/5/input" => (200, "1 2 3\n".into()),
+                "/2023/day/5" if unlocked.load(Ordering::SeqCst) => (200, format!("{PART_1}{PART_2}")),
+                "/2023/day/5" => (200, PART_1.into()),
+                _ => (404, "not found".into()),
+            }
+        });
+
+        let config = stand_in::config(&server.url, stand_in::temp_root("fetch"));
+        let day = Day { year: 2023, day: 5 };
+
+        fetch(&config, day).unwrap();
+        assert_eq!(std::fs::read_to_string(config.input_path(day, "prod")).unwrap(), "1 2 3\n");
+        assert_eq!(std::fs::read_to_string(config.input_path(day, "test")).unwrap(), "1 < 2\n");
+        assert!(!config.input_path(day, "test-2").exists());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.method == "GET" && r.body.is_empty()));
+        assert!(requests.iter().all(|r| r.header("cookie") == Some("session=s3cret")));
+
+        // part 1 isn't solved, so there's nothing new on the page.
+        fetch(&config, day).unwrap();
+        assert_eq!(server.requests().len(), 2);
+
+        // once it is, the page is fetched one more time.
+        std::fs::write(config.input_path(day, "answers"), "0\t1\t42\tcorrect\n").unwrap();
+        unlocked.store(true, Ordering::SeqCst);
+        fetch(&config, day).unwrap();
+        fetch(&config, day).unwrap();
+        assert_eq!(server.requests().len(), 3);
+        assert!(!config.input_path(day, "test-2").exists());
+
+        _ = std::fs::remove_dir_all(&config.root);
+    }
+}
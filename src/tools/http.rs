@@ -0,0 +1,145 @@
+// a tiny http client on top of `curl`, so we don't need a tls stack.
+// requests are spaced out by `Config::min_interval`, also across runs:
+// the time of the last request is kept in `target/aoc-last-request`.
+// the session cookie goes to curl on stdin, so it doesn't show up in `ps`.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+use super::Config;
+
+
+#[derive(Debug)]
+pub enum Error {
+    NoSession,
+    Curl(String),
+    Status { code: u32, body: String },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::NoSession => write!(f, "no session token, set AOC_SESSION or write it to .session"),
+            Error::Curl(msg) => write!(f, "curl failed: {msg}"),
+            Error::Status { code, body } => {
+                let body = body.trim();
+                let body = body.char_indices().nth(200).map_or(body, |(i, _)| &body[..i]);
+                write!(f, "http {code}: {body}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for String {
+    fn from(e: Error) -> String { e.to_string() }
+}
+
+
+pub struct Client<'a> {
+    config: &'a Config,
+    stamp: PathBuf,
+}
+
+impl<'a> Client<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Client { config, stamp: config.root.join("target").join("aoc-last-request") }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, Error> {
+        self.request(path, None)
+    }
+
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, Error> {
+        self.request(path, Some(form))
+    }
+
+    // `form` is sent as `application/x-www-form-urlencoded`.
+    fn request(&self, path: &str, form: Option<&[(&str, &str)]>) -> Result<String, Error> {
+        let session = self.config.session.as_ref().ok_or(Error::NoSession)?;
+
+        self.throttle();
+
+        let url = format!("{}{path}", self.config.base_url);
+        let mut cmd = Command::new("curl");
+        cmd.args(["--silent", "--show-error", "--location"]);
+        cmd.args(["--user-agent", "github.com/leddoo/aoc"]);
+        cmd.args(["--config", "-"]);
+        cmd.args(["--write-out", "\n%{http_code}"]);
+        if let Some(form) = form {
+            for (key, value) in form {
+                cmd.args(["--data-urlencode", &format!("{key}={value}")]);
+            }
+        }
+        cmd.arg(&url);
+
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(|e| Error::Curl(e.to_string()))?;
+        let curl_config = format!("header = \"Cookie: session={}\"\n", quote(session));
+        let written = child.stdin.take().unwrap().write_all(curl_config.as_bytes());
+        let output = child.wait_with_output().map_err(|e| Error::Curl(e.to_string()))?;
+        written.map_err(|e| Error::Curl(e.to_string()))?;
+        self.stamp();
+        if !output.status.success() {
+            return Err(Error::Curl(String::from_utf8_lossy(&output.stderr).trim().into()));
+        }
+
+        let output = String::from_utf8_lossy(&output.stdout);
+        let (body, code) = output.rsplit_once('\n').unwrap_or(("", &output));
+        let code = code.trim().parse().unwrap_or(0);
+        if code != 200 {
+            return Err(Error::Status { code, body: body.into() });
+        }
+        return Ok(body.into());
+    }
+
+    fn throttle(&self) {
+        let Ok(last) = std::fs::read_to_string(&self.stamp) else { return };
+        let Ok(last) = last.trim().parse::<f64>() else { return };
+        let last = SystemTime::UNIX_EPOCH + Duration::from_secs_f64(last);
+        let since = SystemTime::now().duration_since(last).unwrap_or(Duration::ZERO);
+        if since < self.config.min_interval {
+            let wait = self.config.min_interval - since;
+            println!("waiting {wait:.1?} before the next request");
+            std::thread::sleep(wait);
+        }
+    }
+
+    fn stamp(&self) {
+        if let Some(dir) = self.stamp.parent() {
+            _ = std::fs::create_dir_all(dir);
+        }
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or(Duration::ZERO);
+        _ = std::fs::write(&self.stamp, now.as_secs_f64().to_string());
+    }
+}
+
+// for a double quoted string in a curl config file.
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::stand_in::{self, StandIn};
+
+    #[test]
+    fn long_error_body() {
+        // the `é` covers bytes 199 and 200.
+        let body = format!("{}é{}", "x".repeat(199), "y".repeat(100));
+        let server = StandIn::start(move |_| (500, body.clone()));
+        let config = stand_in::config(&server.url, stand_in::temp_root("http"));
+
+        let error = Client::new(&config).get("/2023/day/1").unwrap_err();
+        assert!(matches!(error, Error::Status { code: 500, .. }));
+        assert_eq!(error.to_string(), format!("http 500: {}é", "x".repeat(199)));
+
+        _ = std::fs::remove_dir_all(&config.root);
+    }
+}
@@ -0,0 +1,174 @@
+// command line tools around the puzzles.
+// `cargo run -- fetch 2023 15` etc. without arguments, `main` runs the days.
+
+pub mod http;
+pub mod fetch;
+pub mod submit;
+pub mod scaffold;
+
+#[cfg(test)]
+mod stand_in;
+
+use std::path::PathBuf;
+
+
+pub struct Config {
+    // without trailing slash.
+    pub base_url: String,
+    pub session: Option<String>,
+    // the crate root. inputs live in `src/yYY/`.
+    pub root: PathBuf,
+    pub min_interval: std::time::Duration,
+}
+
+impl Config {
+    // `AOC_URL`, `AOC_SESSION` (or a `.session` file in the root),
+    // `AOC_ROOT`, `AOC_INTERVAL` (seconds).
+    pub fn from_env() -> Config {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        let root = PathBuf::from(var("AOC_ROOT").unwrap_or(env!("CARGO_MANIFEST_DIR").into()));
+
+        let base_url = var("AOC_URL").unwrap_or("https://adventofcode.com".into());
+        let base_url = base_url.trim_end_matches('/').to_string();
+
+        let session = var("AOC_SESSION").or_else(||
+            std::fs::read_to_string(root.join(".session")).ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()));
+
+        let min_interval = var("AOC_INTERVAL")
+            .map(|s| s.parse().expect("AOC_INTERVAL must be a number of seconds"))
+            .unwrap_or(5.0);
+
+        Config {
+            base_url,
+            session,
+            root,
+            min_interval: std::time::Duration::from_secs_f64(min_interval),
+        }
+    }
+
+    pub fn year_dir(&self, day: Day) -> PathBuf {
+        self.root.join("src").join(format!("y{:02}", day.year % 100))
+    }
+
+    // `dNN-prod.txt`, `dNN-test.txt`, `dNN-test-2.txt`, ...
+    pub fn input_path(&self, day: Day, name: &str) -> PathBuf {
+        self.year_dir(day).join(format!("d{:02}-{name}.txt", day.day))
+    }
+
+    // bookkeeping that isn't an input, under `target/aoc/`.
+    pub fn state_path(&self, day: Day, name: &str) -> PathBuf {
+        self.root.join("target").join("aoc").join(format!("{}-d{:02}-{name}", day.year, day.day))
+    }
+}
+
+
+#[derive(Clone, Copy, Debug)]
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+}
+
+impl Day {
+    // accepts `2023 15`, `23 15`, `y23 d15`.
+    pub fn parse(year: &str, day: &str) -> Result<Day, String> {
+        let year = year.trim_start_matches('y');
+        let day = day.trim_start_matches('d');
+
+        let year: u32 = year.parse().map_err(|_| format!("bad year {year:?}"))?;
+        let day: u32 = day.parse().map_err(|_| format!("bad day {day:?}"))?;
+
+        let year = if year < 100 { 2000 + year } else { year };
+        if year < 2015 {
+            return Err(format!("no puzzles in {year}"));
+        }
+        if !(1..=25).contains(&day) {
+            return Err(format!("no day {day}"));
+        }
+        return Ok(Day { year, day });
+    }
+}
+
+
+const USAGE: &str = "\
+usage:
+  aoc                     run the days in `main`
+  aoc fetch <year> <day>  download the input & examples into src/yYY/
+  aoc submit <year> <day> <part> [answer]
+                          submit an answer, or show the previous attempts
+  aoc new <year> <day> [grid]
+                          create src/yYY/dNN.rs & its inputs, optionally with a `Grid`
+  aoc yt <group>...       run the 2022 day 19 solvers from the video, groups are
+                          baseline (slow), pack, u8, thonk, parallel, hash
+  aoc tree [--unpruned] <input> <blueprint> [minutes] [max depth] [max nodes]
+                          write the 2022 day 19 search as target/d19-tree.{dot,html},
+                          or the video's baseline search without pruning";
+
+pub fn command(args: &[String]) -> Result<(), String> {
+    let args = Vec::from_iter(args.iter().map(String::as_str));
+    let config = Config::from_env();
+
+    match args[..] {
+        ["fetch", year, day] => {
+            let day = Day::parse(year, day)?;
+            fetch::fetch(&config, day)
+        }
+
+        ["submit", year, day, part] => {
+            let day = Day::parse(year, day)?;
+            submit::show(&config, day, submit::parse_part(part)?)
+        }
+
+        ["submit", year, day, part, answer] => {
+            let day = Day::parse(year, day)?;
+            submit::submit(&config, day, submit::parse_part(part)?, answer)
+        }
+
+        ["new", year, day] => {
+            scaffold::new_day(&config, Day::parse(year, day)?, false)
+        }
+
+        ["new", year, day, "grid"] => {
+            scaffold::new_day(&config, Day::parse(year, day)?, true)
+        }
+
+        ["yt", ref groups @ ..] if !groups.is_empty() => {
+            crate::y22::d19_yt::main(groups)
+        }
+
+        ["tree", ref rest @ ..] if (2..=6).contains(&rest.len()) => {
+            let (unpruned, rest) = match rest {
+                ["--unpruned", rest @ ..] => (true, rest),
+                _ => (false, rest),
+            };
+            let [input, id, ref rest @ ..] = rest[..] else { return Err(USAGE.into()) };
+            if rest.len() > 3 { return Err(USAGE.into()) }
+
+            let number = |i: usize, default: usize| -> Result<usize, String> {
+                rest.get(i).map_or(Ok(default), |s| s.parse().map_err(|_| format!("bad number {s:?}")))
+            };
+            let id = id.parse().map_err(|_| format!("bad blueprint {id:?}"))?;
+
+            let input = std::fs::read_to_string(input).map_err(|e| format!("{input}: {e}"))?;
+            let minutes = number(0, 24)?;
+            let limit = u8::try_from(minutes).map_err(|_| format!("bad minutes {minutes}"))?;
+            let tree =
+                if unpruned { crate::y22::d19_yt::unpruned_tree(&input, id, limit, number(1, 8)?, number(2, 2000)?)? }
+                else        { crate::y22::d19::search_tree(&input, id, limit, number(1, 8)?, number(2, 2000)?)? };
+
+            let dir = config.root.join("target");
+            std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            for (ext, content) in [("dot", tree.to_dot()), ("html", tree.to_html(&format!("blueprint {id}")))] {
+                let path = dir.join(format!("d19-tree.{ext}"));
+                std::fs::write(&path, content).map_err(|e| format!("{}: {e}", path.display()))?;
+                println!("wrote {}", path.display());
+            }
+            println!("{} nodes, {} not recorded", tree.nodes.len(), tree.dropped);
+            Ok(())
+        }
+
+        _ => Err(USAGE.into()),
+    }
+}
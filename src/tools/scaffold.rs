@@ -0,0 +1,221 @@
+// `new`: creates `src/yYY/dNN.rs` from `day-template.txt`,
+// the (empty) `dNN-test.txt` & `dNN-prod.txt`,
+// and registers the day in `src/yYY/mod.rs` and in its year's calls in `main`.
+// a new year gets its `mod.rs` and a `mod yYY;` in `main.rs`.
+
+use std::path::Path;
+
+use super::{Config, Day};
+
+
+const DAY_TEMPLATE:  &str = include_str!("day-template.txt");
+const GRID_TEMPLATE: &str = include_str!("grid-template.txt");
+
+pub fn new_day(config: &Config, day: Day, grid: bool) -> Result<(), String> {
+    let dir = config.year_dir(day);
+    let year = format!("y{:02}", day.year % 100);
+    let module = format!("d{:02}", day.day);
+
+    // everything is checked & prepared before the first write,
+    // so a failed `new` doesn't leave half a day behind.
+    // the year's directory may exist without a `mod.rs`, `fetch` creates it.
+    let source = dir.join(format!("{module}.rs"));
+    if source.exists() {
+        return Err(format!("{} exists already", source.display()));
+    }
+
+    let mod_path = dir.join("mod.rs");
+    let mods = if mod_path.exists() { read(&mod_path)? } else { String::new() };
+    if mods.lines().any(|l| l.trim_start_matches("//").trim() == format!("pub mod {module};")) {
+        return Err(format!("{} has `{module}` already", mod_path.display()));
+    }
+    let mods = add_mod(&mods, &module);
+
+    let main_path = config.root.join("src").join("main.rs");
+    let mut main = read(&main_path)?;
+    if !main.lines().any(|l| l.trim() == format!("mod {year};")) {
+        main = add_year(&main, &year)?;
+    }
+    let main = add_main_call(&main, &year, &module)?;
+
+    let mut code = String::new();
+    if grid {
+        code.push_str(GRID_TEMPLATE);
+    }
+    code.push_str(&DAY_TEMPLATE.replace("{DAY}", &format!("{:02}", day.day)));
+
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    write(&source, &code)?;
+    for name in ["test", "prod"] {
+        let path = config.input_path(day, name);
+        if !path.exists() {
+            write(&path, "")?;
+        }
+    }
+    write(&mod_path, &mods)?;
+    write(&main_path, &main)?;
+
+    return Ok(());
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|e| format!("{}: {e}", path.display()))?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+
+// keeps the `pub mod dNN;` block sorted.
+// commented out modules count as taken spots.
+fn add_mod(mods: &str, module: &str) -> String {
+    let line = format!("pub mod {module};");
+
+    let mut lines = Vec::from_iter(mods.lines());
+    let at = lines.iter().position(|l| {
+        let name = l.trim_start_matches("//").strip_prefix("pub mod ");
+        name.is_some_and(|name| name.trim_end_matches(';') > module)
+    });
+    let at = at.unwrap_or_else(|| {
+        // after the last module.
+        lines.iter().rposition(|l| l.trim_start_matches("//").starts_with("pub mod "))
+            .map_or(lines.len(), |i| i + 1)
+    });
+    lines.insert(at, &line);
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    return result;
+}
+
+// `mod yYY;` among the other years, sorted.
+fn add_year(main: &str, year: &str) -> Result<String, String> {
+    let mut lines = Vec::from_iter(main.lines());
+    let last = lines.iter().rposition(|l| is_year_mod(l))
+        .ok_or("main.rs: no `mod yYY;` lines")?;
+    let line = format!("mod {year};");
+    let at = lines.iter().position(|l| is_year_mod(l) && *l > line.as_str())
+        .unwrap_or(last + 1);
+
+    lines.insert(at, &line);
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    return Ok(result);
+}
+
+fn is_year_mod(line: &str) -> bool {
+    line.strip_prefix("mod y").and_then(|l| l.strip_suffix(';'))
+        .is_some_and(|l| l.bytes().all(|b| b.is_ascii_digit()))
+}
+
+// `(line, year, day)` of each `yYY::dNN::main();` call, commented out or not.
+fn main_calls<'a>(lines: &[&'a str]) -> Vec<(usize, &'a str, &'a str)> {
+    Vec::from_iter(lines.iter().enumerate().filter_map(|(i, l)| {
+        let l = l.trim_start().trim_start_matches("//");
+        let (year, rest) = l.split_once("::")?;
+        let (day, _) = rest.split_once("::main();")?;
+        (year.starts_with('y') && day.starts_with('d')).then_some((i, year, day))
+    }))
+}
+
+// the call goes into its year's group of calls in `fn main`, sorted by day.
+// a new year gets its own group, between the years around it.
+fn add_main_call(main: &str, year: &str, module: &str) -> Result<String, String> {
+    let mut lines = Vec::from_iter(main.lines());
+    let calls = main_calls(&lines);
+    if calls.is_empty() {
+        return Err("main.rs: no `yYY::dNN::main();` calls".into());
+    }
+
+    let line = format!("    {year}::{module}::main();");
+    let same_year = Vec::from_iter(calls.iter().filter(|(_, y, _)| *y == year));
+    if let Some(first) = same_year.first() {
+        let at = same_year.iter().rev().find(|(_, _, d)| *d < module)
+            .map_or(first.0, |(i, _, _)| i + 1);
+        lines.insert(at, &line);
+    }
+    else if let Some((last, _, _)) = calls.iter().rev().find(|(_, y, _)| *y < year) {
+        lines.insert(last + 1, "");
+        lines.insert(last + 2, &line);
+    }
+    else {
+        let first = calls[0].0;
+        lines.insert(first, "");
+        lines.insert(first, &line);
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    return Ok(result);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::stand_in;
+
+    const MAIN: &str = "\
+mod tools;
+mod y22;
+mod y23;
+
+fn main() {
+    y22::d19::main();
+    y22::d23::main();
+
+    y23::d01::main();
+    //y23::d12::main();
+    y23::d14::main();
+}
+";
+
+    #[test]
+    fn calls_go_into_their_year() {
+        let main = add_main_call(MAIN, "y22", "d20").unwrap();
+        assert!(main.contains("    y22::d19::main();\n    y22::d20::main();\n    y22::d23::main();\n"));
+
+        let main = add_main_call(MAIN, "y23", "d13").unwrap();
+        assert!(main.contains("    //y23::d12::main();\n    y23::d13::main();\n    y23::d14::main();\n"));
+
+        let main = add_main_call(MAIN, "y24", "d01").unwrap();
+        assert!(main.ends_with("    y23::d14::main();\n\n    y24::d01::main();\n}\n"));
+
+        let main = add_main_call(MAIN, "y21", "d01").unwrap();
+        assert!(main.contains("{\n    y21::d01::main();\n\n    y22::d19::main();"));
+    }
+
+    #[test]
+    fn new_year_after_fetch() {
+        let root = stand_in::temp_root("scaffold");
+        let config = stand_in::config("http://unused", root.clone());
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), MAIN).unwrap();
+
+        // what `fetch` leaves behind.
+        let day = Day { year: 2024, day: 1 };
+        std::fs::create_dir_all(config.year_dir(day)).unwrap();
+        std::fs::write(config.input_path(day, "prod"), "input").unwrap();
+
+        new_day(&config, day, false).unwrap();
+        assert_eq!(read(&root.join("src/y24/mod.rs")).unwrap(), "pub mod d01;\n");
+        assert_eq!(read(&config.input_path(day, "prod")).unwrap(), "input");
+        let main = read(&root.join("src/main.rs")).unwrap();
+        assert!(main.contains("mod y23;\nmod y24;\n"));
+        assert!(main.contains("    y24::d01::main();\n"));
+
+        assert!(new_day(&config, day, false).unwrap_err().contains("exists already"));
+
+        // a failed `new` writes nothing.
+        std::fs::write(root.join("src/main.rs"), "mod tools;\n").unwrap();
+        assert!(new_day(&config, Day { year: 2024, day: 2 }, false).is_err());
+        assert!(!root.join("src/y24/d02.rs").exists());
+        assert_eq!(read(&root.join("src/y24/mod.rs")).unwrap(), "pub mod d01;\n");
+
+        _ = std::fs::remove_dir_all(&root);
+    }
+}
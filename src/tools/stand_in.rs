@@ -0,0 +1,102 @@
+// a local http server for the tool tests, so they never talk to the real site.
+// one request per connection, which is all curl needs.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::Config;
+
+
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+pub struct StandIn {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl StandIn {
+    // `respond` returns the status code & body for each request.
+    pub fn start(respond: impl Fn(&Request) -> (u32, String) + Send + 'static) -> StandIn {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let requests = Arc::new(Mutex::new(vec![]));
+        let log = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let Some(request) = read_request(&mut stream) else { continue };
+
+                let (code, body) = respond(&request);
+                log.lock().unwrap().push(request);
+                _ = write!(stream, "HTTP/1.1 {code} stand-in\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+            }
+        });
+
+        StandIn { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn read_request(stream: &mut std::net::TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut words = line.split_whitespace();
+    let method = words.next()?.to_string();
+    let path = words.next()?.to_string();
+
+    let mut headers = vec![];
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() { break }
+
+        let (key, value) = line.split_once(':')?;
+        headers.push((key.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = Request { method, path, headers, body: String::new() };
+    let len = request.header("content-length").and_then(|n| n.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).ok()?;
+    request.body = String::from_utf8(body).ok()?;
+    return Some(request);
+}
+
+
+// an empty crate root in the temp dir.
+pub fn temp_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+    _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    return root;
+}
+
+pub fn config(url: &str, root: PathBuf) -> Config {
+    Config {
+        base_url: url.into(),
+        session: Some("s3cret".into()),
+        root,
+        min_interval: std::time::Duration::ZERO,
+    }
+}
@@ -0,0 +1,422 @@
+// posts answers and keeps every attempt in `dNN-answers.txt` (not checked in),
+// one `<unix time>\t<part>\t<answer>\t<verdict>\t<wait>\t<response>` per line.
+// `<wait>` is how many seconds the server wants before the next answer,
+// `<response>` the text of the server's reply, with `\`, tabs & newlines escaped.
+// the history is checked before posting, so we never send
+// an answer that's known to be wrong or outside the too-high/too-low bracket,
+// and we don't post while the server still wants us to wait.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::{Config, Day};
+use super::http::Client;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    // seconds to wait.
+    Throttled(u64),
+    // the part was solved on the site already.
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn from_response(html: &str) -> Verdict {
+        if html.contains("That's the right answer") {
+            return Verdict::Correct;
+        }
+        if html.contains("You gave an answer too recently") {
+            return Verdict::Throttled(wait_time(html).unwrap_or(60));
+        }
+        if html.contains("You don't seem to be solving the right level") {
+            return Verdict::WrongLevel;
+        }
+        if html.contains("That's not the right answer") {
+            if html.contains("your answer is too high") { return Verdict::TooHigh }
+            if html.contains("your answer is too low")  { return Verdict::TooLow }
+            return Verdict::Wrong;
+        }
+        return Verdict::Unknown;
+    }
+
+    fn to_str(self) -> String {
+        match self {
+            Verdict::Correct     => "correct".into(),
+            Verdict::Wrong       => "wrong".into(),
+            Verdict::TooHigh     => "too-high".into(),
+            Verdict::TooLow      => "too-low".into(),
+            Verdict::Throttled(s) => format!("throttled-{s}"),
+            Verdict::WrongLevel  => "wrong-level".into(),
+            Verdict::Unknown     => "unknown".into(),
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Verdict> {
+        Some(match s {
+            "correct"     => Verdict::Correct,
+            "wrong"       => Verdict::Wrong,
+            "too-high"    => Verdict::TooHigh,
+            "too-low"     => Verdict::TooLow,
+            "wrong-level" => Verdict::WrongLevel,
+            "unknown"     => Verdict::Unknown,
+            _ => Verdict::Throttled(s.strip_prefix("throttled-")?.parse().ok()?),
+        })
+    }
+}
+
+// seconds to wait before the next answer, also after a wrong one.
+pub fn wait_from_response(html: &str) -> u64 {
+    if let Verdict::Throttled(wait) = Verdict::from_response(html) {
+        return wait;
+    }
+    return retry_time(html).unwrap_or(0);
+}
+
+// "Please wait one minute before trying again." or "please wait 5 minutes ..."
+fn retry_time(html: &str) -> Option<u64> {
+    let (_, rest) = html.split_once("lease wait ")?;
+    let (time, _) = rest.split_once(" before trying again")?;
+
+    let (n, unit) = time.split_once(' ')?;
+    let n: u64 = match n {
+        "one" => 1,
+        n => n.parse().ok()?,
+    };
+    return match unit.trim_end_matches('s') {
+        "minute" => Some(60*n),
+        "second" => Some(n),
+        _ => None,
+    };
+}
+
+// "You have 1m 23s left to wait." or "You have 42s left to wait."
+fn wait_time(html: &str) -> Option<u64> {
+    let (_, rest) = html.split_once("You have ")?;
+    let (time, _) = rest.split_once(" left to wait")?;
+
+    let mut seconds = 0;
+    for part in time.split_whitespace() {
+        let (n, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit { "m" => 60*n, "s" => n, _ => return None };
+    }
+    return Some(seconds);
+}
+
+// the text of the reply's `<article>`, without tags.
+pub fn response_text(html: &str) -> String {
+    let article = html.split_once("<article").and_then(|(_, rest)| rest.split_once('>')).map_or(html, |(_, rest)| rest);
+    let article = article.split_once("</article>").map_or(article, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    return Vec::from_iter(text.split_whitespace()).join(" ");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        result.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            _ => return None,
+        });
+    }
+    return Some(result);
+}
+
+
+#[derive(Clone, Debug)]
+pub struct Attempt {
+    // unix seconds.
+    pub time: u64,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    // seconds until we may post again.
+    pub wait: u64,
+    // `response_text` of the reply.
+    pub response: String,
+}
+
+pub struct History {
+    path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn load(config: &Config, day: Day) -> Result<History, String> {
+        let path = config.input_path(day, "answers");
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+
+        let mut attempts = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let bad = || format!("{}:{}: bad attempt {line:?}", path.display(), i + 1);
+
+            let mut fields = line.split('\t');
+            let (Some(time), Some(part), Some(answer), Some(verdict), wait, response, None)
+                = (fields.next(), fields.next(), fields.next(), fields.next(), fields.next(), fields.next(), fields.next())
+                else { return Err(bad()) };
+
+            let verdict = Verdict::from_str(verdict).ok_or_else(bad)?;
+            let wait = match (wait, verdict) {
+                (Some(wait), _) => wait.parse().map_err(|_| bad())?,
+                (None, Verdict::Throttled(wait)) => wait,
+                (None, _) => 0,
+            };
+
+            attempts.push(Attempt {
+                time: time.parse().map_err(|_| bad())?,
+                part: part.parse().map_err(|_| bad())?,
+                answer: answer.into(),
+                verdict,
+                wait,
+                response: unescape(response.unwrap_or("")).ok_or_else(bad)?,
+            });
+        }
+
+        return Ok(History { path, attempts });
+    }
+
+    pub fn add(&mut self, attempt: Attempt) -> Result<(), String> {
+        use std::io::Write;
+
+        let line = format!("{}\t{}\t{}\t{}\t{}\t{}\n",
+            attempt.time, attempt.part, attempt.answer, attempt.verdict.to_str(), attempt.wait, escape(&attempt.response));
+
+        std::fs::OpenOptions::new().create(true).append(true).open(&self.path)
+            .and_then(|mut f| f.write_all(line.as_bytes()))
+            .map_err(|e| format!("{}: {e}", self.path.display()))?;
+
+        self.attempts.push(attempt);
+        return Ok(());
+    }
+
+    pub fn part(&self, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts.iter().filter(move |a| a.part == part)
+    }
+
+    pub fn correct(&self, part: u8) -> Option<&str> {
+        self.part(part).find(|a| a.verdict == Verdict::Correct).map(|a| a.answer.as_str())
+    }
+
+    // the answer is strictly between these.
+    pub fn bracket(&self, part: u8) -> (Option<i64>, Option<i64>) {
+        let mut low  = None::<i64>;
+        let mut high = None::<i64>;
+        for a in self.part(part) {
+            let Ok(n) = a.answer.parse::<i64>() else { continue };
+            match a.verdict {
+                Verdict::TooLow  => low  = Some(low.map_or(n, |l| l.max(n))),
+                Verdict::TooHigh => high = Some(high.map_or(n, |h| h.min(n))),
+                _ => (),
+            }
+        }
+        return (low, high);
+    }
+
+    // seconds left until we may post again.
+    pub fn throttled(&self, now: u64) -> Option<u64> {
+        let last = self.attempts.last()?;
+        (last.time + last.wait).checked_sub(now).filter(|left| *left > 0)
+    }
+
+    // why `answer` shouldn't be posted, if it shouldn't.
+    pub fn check(&self, part: u8, answer: &str, now: u64) -> Option<String> {
+        if let Some(correct) = self.correct(part) {
+            return Some(format!("part {part} is solved already, the answer was {correct}"));
+        }
+
+        if let Some(a) = self.part(part).find(|a| a.answer == answer && is_wrong(a.verdict)) {
+            return Some(format!("{answer} was {} already", a.verdict.to_str()));
+        }
+
+        if let Ok(n) = answer.parse::<i64>() {
+            let (low, high) = self.bracket(part);
+            if let Some(low) = low.filter(|l| n <= *l) {
+                return Some(format!("{answer} is too low, {low} was too low already"));
+            }
+            if let Some(high) = high.filter(|h| n >= *h) {
+                return Some(format!("{answer} is too high, {high} was too high already"));
+            }
+        }
+
+        if let Some(left) = self.throttled(now) {
+            return Some(format!("the server wants us to wait another {left}s"));
+        }
+
+        return None;
+    }
+}
+
+fn is_wrong(verdict: Verdict) -> bool {
+    matches!(verdict, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs()
+}
+
+
+pub fn parse_part(part: &str) -> Result<u8, String> {
+    match part {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("bad part {part:?}, expected 1 or 2")),
+    }
+}
+
+pub fn show(config: &Config, day: Day, part: u8) -> Result<(), String> {
+    let history = History::load(config, day)?;
+
+    for a in history.part(part) {
+        println!("{:>20}  {:<12}  {}", a.answer, a.verdict.to_str(), a.response);
+    }
+    print_bracket(&history, part);
+    return Ok(());
+}
+
+fn print_bracket(history: &History, part: u8) {
+    if let Some(correct) = history.correct(part) {
+        println!("solved: {correct}");
+        return;
+    }
+    match history.bracket(part) {
+        (None, None) => (),
+        (low, high) => {
+            let show = |n: Option<i64>| n.map_or("?".into(), |n| n.to_string());
+            println!("answer is in ({}, {})", show(low), show(high));
+        }
+    }
+}
+
+pub fn submit(config: &Config, day: Day, part: u8, answer: &str) -> Result<(), String> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(format!("bad answer {answer:?}"));
+    }
+
+    let mut history = History::load(config, day)?;
+    if let Some(reason) = history.check(part, answer, now()) {
+        return Err(format!("not submitting: {reason}"));
+    }
+
+    let client = Client::new(config);
+    let part_str = part.to_string();
+    let response = client.post(&format!("/{}/day/{}/answer", day.year, day.day),
+        &[("level", &part_str), ("answer", answer)])?;
+
+    let verdict = Verdict::from_response(&response);
+    let wait = wait_from_response(&response);
+    let response = response_text(&response);
+    history.add(Attempt { time: now(), part, answer: answer.into(), verdict, wait, response })?;
+
+    println!("{answer}: {}", verdict.to_str());
+    print_bracket(&history, part);
+    return Ok(());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::stand_in::{self, StandIn};
+
+    const TOO_LOW: &str = "<article><p>That's not the right answer; your answer is too low. \
+        Please wait one minute before trying again.</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; \
+        you have to wait after submitting an answer before trying again. You have 1m 23s left to wait.</p></article>";
+    const RIGHT: &str = "<article><p>That's the right answer! You are one gold star closer.</p></article>";
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(Verdict::from_response(TOO_LOW), Verdict::TooLow);
+        assert_eq!(wait_from_response(TOO_LOW), 60);
+        assert_eq!(wait_from_response("please wait 5 minutes before trying again."), 300);
+        assert_eq!(Verdict::from_response(TOO_RECENT), Verdict::Throttled(83));
+        assert_eq!(wait_from_response(TOO_RECENT), 83);
+        assert_eq!(Verdict::from_response(RIGHT), Verdict::Correct);
+        assert_eq!(wait_from_response(RIGHT), 0);
+
+        assert_eq!(response_text(&format!("<main>{TOO_LOW}</main>")),
+            "That's not the right answer; your answer is too low. Please wait one minute before trying again.");
+
+        // the response survives the history file.
+        let config = stand_in::config("", stand_in::temp_root("verdicts"));
+        let day = Day { year: 2023, day: 5 };
+        std::fs::create_dir_all(config.year_dir(day)).unwrap();
+        let mut history = History::load(&config, day).unwrap();
+        let response = "tab\there, new\nline, back\\slash \\t".to_string();
+        history.add(Attempt { time: 1, part: 2, answer: "42".into(), verdict: Verdict::Wrong, wait: 60, response: response.clone() }).unwrap();
+        let loaded = History::load(&config, day).unwrap();
+        assert_eq!(loaded.attempts.len(), 1);
+        assert_eq!(loaded.attempts[0].response, response);
+        assert_eq!((loaded.attempts[0].verdict, loaded.attempts[0].wait), (Verdict::Wrong, 60));
+
+        _ = std::fs::remove_dir_all(&config.root);
+    }
+
+    #[test]
+    fn submit_and_refuse() {
+        let server = StandIn::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/2023/day/5/answer") if request.body.contains("answer=10") => (200, TOO_LOW.into()),
+            ("POST", "/2023/day/5/answer") => (200, RIGHT.into()),
+            _ => (404, "not found".into()),
+        });
+
+        let config = stand_in::config(&server.url, stand_in::temp_root("submit"));
+        let day = Day { year: 2023, day: 5 };
+        std::fs::create_dir_all(config.year_dir(day)).unwrap();
+
+        submit(&config, day, 1, "10").unwrap();
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body, "level=1&answer=10");
+
+        // wrong already, below the bracket, and the server wants a minute.
+        assert!(submit(&config, day, 1, "10").is_err());
+        assert!(submit(&config, day, 1, "7").is_err());
+        assert!(submit(&config, day, 1, "12").unwrap_err().contains("wait"));
+        assert_eq!(server.requests().len(), 1);
+
+        let history = History::load(&config, day).unwrap();
+        assert_eq!(history.bracket(1), (Some(10), None));
+        assert_eq!(history.attempts[0].wait, 60);
+        assert!(history.attempts[0].response.starts_with("That's not the right answer"));
+
+        // a minute later.
+        let now = history.attempts[0].time + 60;
+        assert_eq!(history.check(1, "12", now), None);
+
+        _ = std::fs::remove_dir_all(&config.root);
+    }
+}
@@ -0,0 +1,154 @@
+// packing small structs into integers, without `transmute`.
+//
+// ```
+// bit_pack! {
+//     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//     struct Key: u32 {
+//         row:     usize = 16,
+//         pattern: usize = 16,
+//     }
+// }
+// ```
+//
+// fields are unsigned ints, laid out from the low bits up, in order.
+// so eight `u8`s with width 8 give the same `u64` as a transmute
+// on a little endian machine.
+//
+// `to_bits` panics if a field doesn't fit its width, `try_to_bits` returns
+// the `Overflow` instead. `from_bits` ignores the bits above `WIDTH`.
+// the widths are checked against the field types and the target int
+// at compile time.
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    pub ty:    &'static str,
+    pub field: &'static str,
+    pub value: u64,
+    pub width: u32,
+}
+
+impl core::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}::{} = {} doesn't fit in {} bits", self.ty, self.field, self.value, self.width)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+
+#[inline(always)]
+pub const fn mask(width: u32) -> u64 {
+    if width >= 64 { u64::MAX } else { (1 << width) - 1 }
+}
+
+
+macro_rules! bit_pack {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident : $int:ty {
+            $($fvis:vis $field:ident : $fty:ty = $width:expr),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($fvis $field: $fty,)*
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            pub const WIDTH: u32 = 0 $(+ $width)*;
+
+            const CHECK: () = {
+                assert!(<$int>::BITS <= 64, "bit_pack: target int is wider than u64");
+                assert!(Self::WIDTH <= <$int>::BITS, "bit_pack: fields don't fit the target int");
+                $(assert!($width > 0 && $width <= <$fty>::BITS, "bit_pack: bad field width");)*
+            };
+
+            #[inline]
+            pub fn try_to_bits(self) -> Result<$int, $crate::util::bits::Overflow> {
+                let () = Self::CHECK;
+
+                let mut bits = 0u64;
+                let mut shift = 0u32;
+                $(
+                    let value = self.$field as u64;
+                    if value > $crate::util::bits::mask($width) {
+                        return Err($crate::util::bits::Overflow {
+                            ty:    stringify!($name),
+                            field: stringify!($field),
+                            value,
+                            width: $width,
+                        });
+                    }
+                    bits |= value << shift;
+                    shift += $width;
+                )*
+                let _ = shift;
+                return Ok(bits as $int);
+            }
+
+            #[inline]
+            pub fn to_bits(self) -> $int {
+                match self.try_to_bits() {
+                    Ok(bits) => bits,
+                    Err(e) => panic!("{}", e),
+                }
+            }
+
+            #[inline]
+            pub fn from_bits(bits: $int) -> Self {
+                let () = Self::CHECK;
+
+                let bits = bits as u64;
+                let mut shift = 0u32;
+                $(
+                    let $field = ((bits >> shift) & $crate::util::bits::mask($width)) as $fty;
+                    shift += $width;
+                )*
+                let _ = shift;
+                return Self { $($field,)* };
+            }
+        }
+    };
+}
+
+pub(crate) use bit_pack;
+
+
+#[cfg(test)]
+mod tests {
+    bit_pack! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct Small: u16 {
+            a: u8  = 3,
+            b: u32 = 12,
+            c: u8  = 1,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for x in [
+            Small { a: 0, b: 0,     c: 0 },
+            Small { a: 7, b: 4095,  c: 1 },
+            Small { a: 5, b: 0x9a3, c: 0 },
+        ] {
+            assert_eq!(Small::from_bits(x.to_bits()), x);
+        }
+        assert_eq!(Small { a: 1, b: 2, c: 1 }.to_bits(), 1 | 2 << 3 | 1 << 15);
+        assert_eq!(Small::WIDTH, 16);
+    }
+
+    #[test]
+    fn overflow() {
+        let e = Small { a: 8, b: 0, c: 0 }.try_to_bits().unwrap_err();
+        assert_eq!(e, super::Overflow { ty: "Small", field: "a", value: 8, width: 3 });
+        assert_eq!(e.to_string(), "Small::a = 8 doesn't fit in 3 bits");
+
+        let e = Small { a: 0, b: 4096, c: 0 }.try_to_bits().unwrap_err();
+        assert_eq!((e.field, e.value), ("b", 4096));
+
+        assert!(std::panic::catch_unwind(|| Small { a: 0, b: 0, c: 2 }.to_bits()).is_err());
+    }
+}
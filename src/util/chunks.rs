@@ -0,0 +1,70 @@
+// splitting inputs into chunks that can be solved in parallel.
+
+use rayon::prelude::*;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Split {
+    // chunks end after a `\n`.
+    Lines,
+    // chunks end between blocks separated by a blank line.
+    Paragraphs,
+}
+
+impl Split {
+    fn separator(self) -> &'static [u8] {
+        match self {
+            Split::Lines      => b"\n",
+            Split::Paragraphs => b"\n\n",
+        }
+    }
+}
+
+// small inputs aren't worth the threads.
+pub fn default_count(input: &str) -> usize {
+    if input.len() < 128 { 1 } else { rayon::current_num_threads() }
+}
+
+// up to `n` non-empty chunks of roughly equal size.
+pub fn split(input: &str, n: usize, split: Split) -> Vec<&str> {
+    let sep = split.separator();
+    let bytes = input.as_bytes();
+
+    let mut result = Vec::with_capacity(n);
+    let mut begin = 0;
+    for i in 1..=n {
+        let mut end = input.len();
+
+        if i < n {
+            // move back to just after the last separator before the target.
+            end = input.len() * i / n;
+            while end > begin && !bytes[..end].ends_with(sep) {
+                end -= 1;
+            }
+        }
+
+        if end > begin {
+            // keep the chunk's own newline, drop the blank line after it,
+            // so each chunk looks like a complete input.
+            // the separators are ascii, so these are char boundaries.
+            let trim = if end < input.len() { sep.len() - 1 } else { 0 };
+            result.push(&input[begin..end - trim]);
+            begin = end;
+        }
+    }
+    return result;
+}
+
+pub fn par_map<T: Send>(input: &str, n: usize, split: Split, f: impl Fn(&str) -> T + Sync + Send) -> Vec<T> {
+    self::split(input, n, split)
+    .into_par_iter()
+    .map(f)
+    .collect()
+}
+
+pub fn par_sum<T: Send + core::iter::Sum>(input: &str, split: Split, f: impl Fn(&str) -> T + Sync + Send) -> T {
+    self::split(input, default_count(input), split)
+    .into_par_iter()
+    .map(f)
+    .sum()
+}
@@ -0,0 +1,62 @@
+// rustc's fxhash: rotate, xor in a word, multiply.
+// fast and good enough for small keys, but not dos resistant.
+
+pub const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+#[inline(always)]
+pub fn fx(key: u64) -> u64 {
+    key.wrapping_mul(SEED)
+}
+
+// for keys that don't fit in a `u64`.
+pub fn fx_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = FxHasher::default();
+    core::hash::Hasher::write(&mut hasher, bytes);
+    return hasher.hash;
+}
+
+
+// as a `Hasher`, for std `HashMap`s with small keys.
+#[derive(Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+pub type FxBuildHasher = core::hash::BuildHasherDefault<FxHasher>;
+
+impl FxHasher {
+    #[inline(always)]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl core::hash::Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let (chunks, rest) = bytes.as_chunks::<8>();
+        for chunk in chunks {
+            self.add(u64::from_le_bytes(*chunk));
+        }
+        for b in rest {
+            self.add(*b as u64);
+        }
+    }
+
+    #[inline]
+    fn write_u32(&mut self, n: u32) { self.add(n as u64) }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) { self.add(n) }
+
+    #[inline]
+    fn write_usize(&mut self, n: usize) { self.add(n as u64) }
+
+    #[inline]
+    fn write_i32(&mut self, n: i32) { self.add(n as u32 as u64) }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
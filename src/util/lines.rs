@@ -0,0 +1,176 @@
+// newline search for line oriented inputs.
+// all finders return the same thing as `find_newline_scalar`.
+
+
+#[inline]
+pub fn find_newline_scalar(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|b| *b == b'\n')
+}
+
+#[inline]
+pub fn find_newline_swar(bytes: &[u8]) -> Option<usize> {
+    let mut chunks = bytes.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+
+        // zero bytes where the newlines are.
+        // borrows only propagate upwards, so the lowest flag is exact.
+        let word = word ^ 0x0a0a0a0a0a0a0a0a;
+        let zero_or_high = word.wrapping_sub(0x0101010101010101);
+        let not_high = !word & 0x8080808080808080;
+        let mask = zero_or_high & not_high;
+
+        if mask != 0 {
+            return Some(offset + (mask.trailing_zeros() / 8) as usize);
+        }
+        offset += 8;
+    }
+
+    find_newline_scalar(chunks.remainder()).map(|i| offset + i)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    // safety: caller must ensure sse2 is available.
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn find_newline_sse2(bytes: &[u8]) -> Option<usize> {
+        let nl = _mm_set1_epi8(b'\n' as i8);
+
+        let mut chunks = bytes.chunks_exact(16);
+        let mut offset = 0;
+        for chunk in &mut chunks {
+            // `chunk` is exactly 16 bytes.
+            let v = unsafe { _mm_loadu_si128(chunk.as_ptr().cast()) };
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(v, nl)) as u32;
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += 16;
+        }
+
+        super::find_newline_swar(chunks.remainder()).map(|i| offset + i)
+    }
+
+    // safety: caller must ensure avx2 is available.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn find_newline_avx2(bytes: &[u8]) -> Option<usize> {
+        let nl = _mm256_set1_epi8(b'\n' as i8);
+
+        let mut chunks = bytes.chunks_exact(32);
+        let mut offset = 0;
+        for chunk in &mut chunks {
+            // `chunk` is exactly 32 bytes.
+            let v = unsafe { _mm256_loadu_si256(chunk.as_ptr().cast()) };
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(v, nl)) as u32;
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += 32;
+        }
+
+        unsafe { find_newline_sse2(chunks.remainder()) }.map(|i| offset + i)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub fn find_newline_sse2(bytes: &[u8]) -> Option<usize> {
+    // sse2 is part of the x86_64 baseline.
+    unsafe { x86::find_newline_sse2(bytes) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub fn find_newline_avx2(bytes: &[u8]) -> Option<usize> {
+    if std::is_x86_feature_detected!("avx2") {
+        unsafe { x86::find_newline_avx2(bytes) }
+    }
+    else { find_newline_sse2(bytes) }
+}
+
+// the fastest finder the cpu supports.
+#[inline]
+pub fn find_newline(bytes: &[u8]) -> Option<usize> {
+    #[cfg(target_arch = "x86_64")]
+    return find_newline_avx2(bytes);
+
+    #[cfg(not(target_arch = "x86_64"))]
+    return find_newline_swar(bytes);
+}
+
+
+// like `str::lines`, but only splits on `\n`.
+// a trailing newline doesn't produce an empty line.
+pub fn byte_lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut input = input;
+    core::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+
+        let (line, rest) = match find_newline(input) {
+            Some(len) => (&input[..len], &input[len+1..]),
+            None      => (input, &input[input.len()..]),
+        };
+        input = rest;
+        return Some(line);
+    })
+}
+
+// like `str::lines`: splits on `\n` and drops a `\r` before it.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    let mut input = input;
+    core::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+
+        // `\n` is ascii, so these are char boundaries.
+        let (line, rest) = match find_newline(input.as_bytes()) {
+            Some(len) => {
+                let line = &input[..len];
+                (line.strip_suffix('\r').unwrap_or(line), &input[len+1..])
+            }
+            None => (input, ""),
+        };
+        input = rest;
+        return Some(line);
+    })
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // compares every finder against the scalar one, at every offset.
+    fn check_finders(bytes: &[u8]) {
+        for i in 0..=bytes.len() {
+            let rest = &bytes[i..];
+            let expected = find_newline_scalar(rest);
+            assert_eq!(find_newline_swar(rest), expected, "swar at {i}");
+            #[cfg(target_arch = "x86_64")] {
+                assert_eq!(find_newline_sse2(rest), expected, "sse2 at {i}");
+                assert_eq!(find_newline_avx2(rest), expected, "avx2 at {i}");
+            }
+            assert_eq!(find_newline(rest), expected, "find_newline at {i}");
+        }
+    }
+
+    #[test]
+    fn finders() {
+        check_finders(include_str!("../y23/d01-prod.txt").as_bytes());
+        check_finders(b"\n\nno newline at the end, which is longer than one avx2 register");
+    }
+
+    #[test]
+    fn lines_like_str_lines() {
+        for input in ["", "a", "a\n", "a\nb", "a\r\nb\r\n", "\r\n\n", "a\rb\n", "\r"] {
+            assert_eq!(Vec::from_iter(lines(input)), Vec::from_iter(input.lines()), "{input:?}");
+        }
+    }
+}
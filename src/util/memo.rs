@@ -0,0 +1,210 @@
+// a fixed size memo / transposition table for `u64` keys.
+//
+// open addressing with a short linear probe.
+// when the probe is full, the entry in the key's home slot is the victim,
+// and `Replace` decides whether the new entry takes its place.
+// so lookups can miss entries that were inserted earlier,
+// which is fine for caches of pure functions.
+
+use core::marker::PhantomData;
+
+use super::hash;
+use super::stats;
+
+
+pub trait KeyHash {
+    fn index(key: u64, bits: u32) -> usize;
+}
+
+// the key's low bits, for keys that are already well spread,
+// like indices or positions.
+pub struct Identity;
+
+impl KeyHash for Identity {
+    #[inline(always)]
+    fn index(key: u64, bits: u32) -> usize {
+        (key & ((1 << bits) - 1)) as usize
+    }
+}
+
+// rustc's fxhash: one multiply, then the high bits.
+// good for packed structs, where the low bits of the key barely change.
+pub struct Fx;
+
+impl Fx {
+    #[inline(always)]
+    pub fn hash(key: u64) -> u64 {
+        hash::fx(key)
+    }
+
+    // for keys that don't fit in a `u64`.
+    pub fn hash_bytes(bytes: &[u8]) -> u64 {
+        hash::fx_bytes(bytes)
+    }
+}
+
+impl KeyHash for Fx {
+    #[inline(always)]
+    fn index(key: u64, bits: u32) -> usize {
+        if bits == 0 { return 0 }
+        (Self::hash(key) >> (64 - bits)) as usize
+    }
+}
+
+
+#[allow(dead_code)]
+pub enum Replace<V> {
+    Always,
+    Never,
+    // `f(old, new)`: whether `new` should replace `old`.
+    When(fn(&V, &V) -> bool),
+}
+
+impl<V> Replace<V> {
+    #[inline(always)]
+    fn allows(&self, old: &V, new: &V) -> bool {
+        match self {
+            Replace::Always => true,
+            Replace::Never  => false,
+            Replace::When(f) => f(old, new),
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoStats {
+    pub refs: u64,
+    pub hits: u64,
+    pub inserts: u64,
+    pub updates: u64,
+    pub evictions: u64,
+    // inserts that lost against the old entry.
+    pub rejects: u64,
+}
+
+impl core::fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "refs {}, hits {} ({:.0}%), inserts {}, updates {}, evictions {}, rejects {}",
+            self.refs, self.hits, self.hits as f64 / self.refs.max(1) as f64 * 100.0,
+            self.inserts, self.updates, self.evictions, self.rejects)
+    }
+}
+
+
+const EMPTY: u64 = u64::MAX;
+const PROBES: usize = 4;
+
+pub struct MemoTable<V: Copy + Default, H: KeyHash = Fx> {
+    keys: Vec<u64>,
+    values: Vec<V>,
+    bits: u32,
+    len: usize,
+    replace: Replace<V>,
+    stats: MemoStats,
+    hash: PhantomData<H>,
+}
+
+impl<V: Copy + Default, H: KeyHash> MemoTable<V, H> {
+    // `capacity` is rounded up to a power of two.
+    pub fn new(capacity: usize, replace: Replace<V>) -> Self {
+        let capacity = capacity.max(PROBES).next_power_of_two();
+        MemoTable {
+            keys: vec![EMPTY; capacity],
+            values: vec![V::default(); capacity],
+            bits: capacity.trailing_zeros(),
+            len: 0,
+            replace,
+            stats: MemoStats::default(),
+            hash: PhantomData,
+        }
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.keys.fill(EMPTY);
+        self.len = 0;
+    }
+
+    #[inline]
+    fn slot(&self, home: usize, i: usize) -> usize {
+        (home + i) & (self.keys.len() - 1)
+    }
+
+    #[inline]
+    pub fn get(&mut self, key: u64) -> Option<V> {
+        debug_assert!(key != EMPTY);
+        self.stats.refs += 1;
+        stats::with(|s| s.memo_refs += 1);
+
+        let home = H::index(key, self.bits);
+        for i in 0..PROBES {
+            let slot = self.slot(home, i);
+            let k = self.keys[slot];
+            if k == key {
+                self.stats.hits += 1;
+                stats::with(|s| s.memo_hits += 1);
+                return Some(self.values[slot]);
+            }
+            if k == EMPTY {
+                break;
+            }
+        }
+        return None;
+    }
+
+    // an existing entry for `key` is also subject to `Replace`.
+    #[inline]
+    pub fn insert(&mut self, key: u64, value: V) {
+        debug_assert!(key != EMPTY);
+
+        let home = H::index(key, self.bits);
+        for i in 0..PROBES {
+            let slot = self.slot(home, i);
+            let k = self.keys[slot];
+            if k == key {
+                if self.replace.allows(&self.values[slot], &value) {
+                    self.values[slot] = value;
+                    self.stats.updates += 1;
+                }
+                else {
+                    self.stats.rejects += 1;
+                }
+                return;
+            }
+            if k == EMPTY {
+                self.keys[slot]   = key;
+                self.values[slot] = value;
+                self.len += 1;
+                self.stats.inserts += 1;
+                return;
+            }
+        }
+
+        if self.replace.allows(&self.values[home], &value) {
+            self.keys[home]   = key;
+            self.values[home] = value;
+            self.stats.evictions += 1;
+        }
+        else {
+            self.stats.rejects += 1;
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod chunks;
+pub mod lines;
+pub mod parse;
+pub mod pattern;
+pub mod stats;
+pub mod tree;
+pub mod hash;
+pub mod memo;
+pub mod bits;
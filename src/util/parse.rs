@@ -0,0 +1,225 @@
+// cursor based parsing of puzzle inputs, without allocating or `str::parse`.
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    ExpectedDigit,
+    ExpectedLiteral,
+    Overflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    // byte offset into the cursor's input.
+    pub pos: usize,
+    pub kind: ErrorKind,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let what = match self.kind {
+            ErrorKind::UnexpectedEnd   => "unexpected end of input",
+            ErrorKind::ExpectedDigit   => "expected a digit",
+            ErrorKind::ExpectedLiteral => "expected literal",
+            ErrorKind::Overflow        => "number too large",
+        };
+        write!(f, "{what} at byte {}", self.pos)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+
+// all eight bytes of `v` are ascii digits.
+#[inline]
+fn is_eight_digits(v: u64) -> bool {
+    ((v & 0xF0F0F0F0F0F0F0F0) | (((v.wrapping_add(0x0606060606060606)) & 0xF0F0F0F0F0F0F0F0) >> 4))
+        == 0x3333333333333333
+}
+
+// value of eight ascii digits, first digit in the lowest byte.
+#[inline]
+fn parse_eight_digits(v: u64) -> u64 {
+    const MASK: u64 = 0x000000FF000000FF;
+    const MUL1: u64 = 100 + (1_000_000 << 32);
+    const MUL2: u64 = 1 + (10_000 << 32);
+
+    let v = v.wrapping_sub(0x3030303030303030);
+    // pairs of digits.
+    let v = v.wrapping_mul(10).wrapping_add(v >> 8);
+    // pairs into the final value.
+    (((v & MASK).wrapping_mul(MUL1)).wrapping_add(((v >> 16) & MASK).wrapping_mul(MUL2))) >> 32
+}
+
+
+#[derive(Clone, Debug)]
+pub struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    #[inline]
+    pub fn new(input: &'a str) -> Self {
+        Self { bytes: input.as_bytes(), pos: 0 }
+    }
+
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    pub fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    #[inline]
+    pub fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    #[inline]
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        ParseError { pos: self.pos, kind }
+    }
+
+    #[inline]
+    pub fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    // moves to the next `b`, without consuming it.
+    #[allow(dead_code)]
+    pub fn skip_until(&mut self, b: u8) -> Result<(), ParseError> {
+        match self.rest().iter().position(|at| *at == b) {
+            Some(i) => { self.pos += i; Ok(()) }
+            None    => { self.pos = self.bytes.len(); Err(self.error(ErrorKind::UnexpectedEnd)) }
+        }
+    }
+
+    // moves to the next digit or `-`. returns false if there is none.
+    pub fn skip_until_number(&mut self) -> bool {
+        while let Some(b) = self.peek() {
+            if b.is_ascii_digit() || b == b'-' { return true }
+            self.pos += 1;
+        }
+        return false;
+    }
+
+    pub fn expect_literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.rest().starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        }
+        else {
+            Err(self.error(ErrorKind::ExpectedLiteral))
+        }
+    }
+
+    // consumes `literal` if it's next.
+    #[inline]
+    pub fn eat(&mut self, literal: &str) -> bool {
+        self.expect_literal(literal).is_ok()
+    }
+
+    // skips leading spaces.
+    pub fn next_u64(&mut self) -> Result<u64, ParseError> {
+        self.skip_spaces();
+
+        match self.peek() {
+            None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+            Some(b) if !b.is_ascii_digit() => return Err(self.error(ErrorKind::ExpectedDigit)),
+            _ => (),
+        }
+
+        let start = self.pos;
+        let mut result = 0u64;
+
+        // eight digits at a time.
+        while let Some(chunk) = self.rest().first_chunk::<8>() {
+            let v = u64::from_le_bytes(*chunk);
+            if !is_eight_digits(v) { break }
+
+            result = result.checked_mul(100_000_000)
+                .and_then(|r| r.checked_add(parse_eight_digits(v)))
+                .ok_or(ParseError { pos: start, kind: ErrorKind::Overflow })?;
+            self.pos += 8;
+        }
+
+        while let Some(b) = self.peek() {
+            if !b.is_ascii_digit() { break }
+
+            result = result.checked_mul(10)
+                .and_then(|r| r.checked_add((b - b'0') as u64))
+                .ok_or(ParseError { pos: start, kind: ErrorKind::Overflow })?;
+            self.pos += 1;
+        }
+
+        return Ok(result);
+    }
+
+    // skips leading spaces. accepts a leading `-` or `+`.
+    pub fn next_i64(&mut self) -> Result<i64, ParseError> {
+        self.skip_spaces();
+
+        let start = self.pos;
+        let negative = self.eat("-");
+        if !negative { self.eat("+"); }
+
+        let magnitude = self.next_u64()?;
+        let overflow = ParseError { pos: start, kind: ErrorKind::Overflow };
+        if negative {
+            0i64.checked_sub_unsigned(magnitude).ok_or(overflow)
+        }
+        else {
+            i64::try_from(magnitude).map_err(|_| overflow)
+        }
+    }
+
+    // the next space separated number on this line.
+    // `None` at the end of the line, which is consumed.
+    #[allow(dead_code)]
+    pub fn next_u64_in_line(&mut self) -> Option<Result<u64, ParseError>> {
+        self.skip_spaces();
+        if self.is_done() || self.eat("\n") {
+            return None;
+        }
+        Some(self.next_u64())
+    }
+
+    pub fn next_i64_in_line(&mut self) -> Option<Result<i64, ParseError>> {
+        self.skip_spaces();
+        if self.is_done() || self.eat("\n") {
+            return None;
+        }
+        Some(self.next_i64())
+    }
+
+    // the rest of the line's numbers.
+    #[allow(dead_code)]
+    pub fn u64s(&mut self) -> Result<Vec<u64>, ParseError> {
+        core::iter::from_fn(|| self.next_u64_in_line()).collect()
+    }
+
+    pub fn i64s(&mut self) -> Result<Vec<i64>, ParseError> {
+        core::iter::from_fn(|| self.next_i64_in_line()).collect()
+    }
+
+    // numbers separated by `sep`, like `1,1,3`.
+    pub fn list_u64(&mut self, sep: &str) -> Result<Vec<u64>, ParseError> {
+        let mut result = vec![self.next_u64()?];
+        while self.eat(sep) {
+            result.push(self.next_u64()?);
+        }
+        return Ok(result);
+    }
+}
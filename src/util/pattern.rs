@@ -0,0 +1,191 @@
+// `parse!(line, "Card " {_} ": " {Vec<u64>} " | " {Vec<u64>})`.
+//
+// string literals must match exactly.
+// `{T}` is a field, converted to `T`. `{_}` is a field that's skipped.
+// a field extends up to the first occurrence of the literal after it,
+// or to the end of the input for the last one.
+// one field gives `Result<T, PatternError>`, several give a tuple.
+
+use super::parse::{Cursor, ParseError};
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    // byte offset into the input.
+    pub pos: usize,
+    pub message: String,
+}
+
+impl core::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.pos)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+
+pub trait Field<'a>: Sized {
+    const NAME: &'static str;
+
+    // errors are relative to the start of `text`.
+    fn from_field(text: &'a str) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_int_field {
+    ($($ty:ty => $via:ident),*) => { $(
+        impl<'a> Field<'a> for $ty {
+            const NAME: &'static str = stringify!($ty);
+
+            fn from_field(text: &'a str) -> Result<Self, ParseError> {
+                let mut c = Cursor::new(text);
+                let start = { c.skip_spaces(); c.pos() };
+                let value = c.$via()?;
+                let value = <$ty>::try_from(value).map_err(|_|
+                    ParseError { pos: start, kind: super::parse::ErrorKind::Overflow })?;
+
+                c.skip_spaces();
+                if !c.is_done() {
+                    return Err(ParseError { pos: c.pos(), kind: super::parse::ErrorKind::ExpectedDigit });
+                }
+                Ok(value)
+            }
+        }
+    )* };
+}
+
+impl_int_field!(
+    u8 => next_u64, u16 => next_u64, u32 => next_u64, u64 => next_u64, usize => next_u64,
+    i8 => next_i64, i16 => next_i64, i32 => next_i64, i64 => next_i64, isize => next_i64);
+
+impl<'a> Field<'a> for &'a str {
+    const NAME: &'static str = "&str";
+
+    fn from_field(text: &'a str) -> Result<Self, ParseError> {
+        Ok(text.trim_matches(' '))
+    }
+}
+
+// items separated by spaces and/or commas.
+impl<'a, T: Field<'a>> Field<'a> for Vec<T> {
+    const NAME: &'static str = "Vec";
+
+    fn from_field(text: &'a str) -> Result<Self, ParseError> {
+        let base = text.as_ptr() as usize;
+        text.split([' ', ',']).filter(|item| !item.is_empty())
+        .map(|item| {
+            let offset = item.as_ptr() as usize - base;
+            T::from_field(item).map_err(|e| ParseError { pos: offset + e.pos, ..e })
+        })
+        .collect()
+    }
+}
+
+
+// the helpers `parse!` expands to.
+
+pub fn literal(input: &str, pos: usize, literal: &str) -> Result<usize, PatternError> {
+    if !input[pos..].starts_with(literal) {
+        return Err(PatternError { pos, message: format!("expected {literal:?}") });
+    }
+    return Ok(pos + literal.len());
+}
+
+// end of the field at `pos`, which is followed by `next`.
+pub fn until(input: &str, pos: usize, next: &str) -> Result<usize, PatternError> {
+    match input[pos..].find(next) {
+        Some(len) => Ok(pos + len),
+        None => Err(PatternError { pos, message: format!("expected {next:?} after field") }),
+    }
+}
+
+pub fn done(input: &str, pos: usize) -> Result<(), PatternError> {
+    if pos != input.len() {
+        return Err(PatternError { pos, message: "unexpected trailing input".into() });
+    }
+    return Ok(());
+}
+
+pub fn field<'a, T: Field<'a>>(text: &'a str, offset: usize, index: usize) -> Result<T, PatternError> {
+    T::from_field(text).map_err(|e| PatternError {
+        pos: offset + e.pos,
+        message: format!("field {index} ({}): {:?} in {text:?}", T::NAME, e.kind),
+    })
+}
+
+
+// the pattern is expanded token by token, so there's no runtime pattern parsing.
+// each field value is bound in its own expansion, and hygiene keeps them apart,
+// so they can be collected into the result at the end.
+macro_rules! parse {
+    // literal.
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; $lit:literal $($rest:tt)*) => {{
+        let $pos = $crate::util::pattern::literal($input, $pos, $lit)?;
+        $crate::util::pattern::parse!(@go $input, $pos, $index, [$($values)*]; $($rest)*)
+    }};
+
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; {$($a:tt)*} {$($b:tt)*} $($rest:tt)*) => {
+        compile_error!("parse!: fields need a literal between them")
+    };
+
+    // skipped field.
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; {_} $lit:literal $($rest:tt)*) => {{
+        let $pos = $crate::util::pattern::until($input, $pos, $lit)?;
+        $crate::util::pattern::parse!(@go $input, $pos, $index, [$($values)*]; $lit $($rest)*)
+    }};
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; {_}) => {{
+        let _ = $pos;
+        $crate::util::pattern::parse!(@done [$($values)*])
+    }};
+
+    // field.
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; {$ty:ty} $lit:literal $($rest:tt)*) => {{
+        let end = $crate::util::pattern::until($input, $pos, $lit)?;
+        let value = $crate::util::pattern::field::<$ty>(&$input[$pos..end], $pos, $index)?;
+        $crate::util::pattern::parse!(@go $input, end, $index + 1, [$($values)* value]; $lit $($rest)*)
+    }};
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; {$ty:ty}) => {{
+        let value = $crate::util::pattern::field::<$ty>(&$input[$pos..], $pos, $index)?;
+        $crate::util::pattern::parse!(@done [$($values)* value])
+    }};
+
+    // end of the pattern.
+    (@go $input:ident, $pos:ident, $index:expr, [$($values:ident)*]; ) => {{
+        $crate::util::pattern::done($input, $pos)?;
+        $crate::util::pattern::parse!(@done [$($values)*])
+    }};
+
+    (@done [$value:ident]) => { Ok($value) };
+    (@done [$($values:ident)*]) => { Ok(($($values,)*)) };
+
+    ($input:expr, $($pattern:tt)+) => {
+        (|| -> Result<_, $crate::util::pattern::PatternError> {
+            let input: &str = $input;
+            let pos = 0usize;
+            $crate::util::pattern::parse!(@go input, pos, 0usize, []; $($pattern)+)
+        })()
+    };
+}
+
+pub(crate) use parse;
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn fields() {
+        assert_eq!(parse!("Card 3: 1 2 | 3", "Card " {_} ": " {Vec<u8>} " | " {Vec<u8>}), Ok((vec![1, 2], vec![3])));
+        assert_eq!(parse!("AAA = (BBB, CCC)", {&str} " = (" {&str} ", " {&str} ")"), Ok(("AAA", "BBB", "CCC")));
+        assert_eq!(parse!("seeds: 7", "seeds: " {u32}), Ok(7));
+        assert_eq!(parse!("x 7", {_} " " {_}), Ok(()));
+    }
+
+    #[test]
+    fn errors() {
+        let e = parse!("Card 3: 1 x", "Card " {_} ": " {Vec<u8>}).unwrap_err();
+        assert_eq!(e.pos, 10);
+        assert_eq!(parse!("seeds 7", "seeds: " {u32}).unwrap_err().pos, 0);
+        assert_eq!(parse!("7 and more", {u32} " and").unwrap_err().pos, 5);
+    }
+}
@@ -0,0 +1,105 @@
+// counters for searches: visited states, memo lookups, pruned branches.
+//
+// only counted with `--features stats`, otherwise `with` compiles to nothing.
+// each thread counts into its own `Stats`, `take` merges them:
+// rayon's workers are flushed with a broadcast, other threads flush when they exit.
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+
+pub const ENABLED: bool = cfg!(feature = "stats");
+
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stats {
+    pub states_visited: u64,
+    pub memo_refs: u64,
+    pub memo_hits: u64,
+    pub pruned: u64,
+    // states a memo hit saved us from visiting.
+    pub states_skipped: u128,
+}
+
+impl Stats {
+    pub const fn new() -> Self {
+        Stats { states_visited: 0, memo_refs: 0, memo_hits: 0, pruned: 0, states_skipped: 0 }
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        self.states_visited += other.states_visited;
+        self.memo_refs      += other.memo_refs;
+        self.memo_hits      += other.memo_hits;
+        self.pruned         += other.pruned;
+        self.states_skipped += other.states_skipped;
+    }
+}
+
+impl core::fmt::Display for Stats {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "states visited: {}", self.states_visited)?;
+        writeln!(f, "pruned: {}", self.pruned)?;
+        if self.memo_refs != 0 {
+            writeln!(f, "memo refs: {}", self.memo_refs)?;
+            writeln!(f, "memo hits: {} ({:.0}%)", self.memo_hits, self.memo_hits as f64 / self.memo_refs as f64 * 100.0)?;
+        }
+        if self.states_skipped != 0 {
+            writeln!(f, "states skipped: {}", self.states_skipped)?;
+        }
+        Ok(())
+    }
+}
+
+
+struct Local(RefCell<Stats>);
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        merge_into_total(self.0.get_mut());
+    }
+}
+
+thread_local! {
+    static LOCAL: Local = const { Local(RefCell::new(Stats::new())) };
+}
+
+static TOTAL: Mutex<Stats> = Mutex::new(Stats::new());
+
+fn merge_into_total(stats: &mut Stats) {
+    let stats = core::mem::take(stats);
+    TOTAL.lock().unwrap_or_else(|e| e.into_inner()).merge(&stats);
+}
+
+fn flush() {
+    _ = LOCAL.try_with(|l| merge_into_total(&mut l.0.borrow_mut()));
+}
+
+
+#[inline(always)]
+pub fn with<F: FnOnce(&mut Stats)>(f: F) {
+    if ENABLED {
+        LOCAL.with(|l| f(&mut l.0.borrow_mut()));
+    }
+}
+
+// the counts of all threads since the last `take`.
+pub fn take() -> Stats {
+    if !ENABLED {
+        return Stats::new();
+    }
+
+    flush();
+    rayon::broadcast(|_| flush());
+    return core::mem::take(&mut *TOTAL.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+pub fn reset() {
+    take();
+}
+
+// for the runners, after the result.
+pub fn print() {
+    if ENABLED {
+        print!("{}", take());
+    }
+}
@@ -0,0 +1,212 @@
+// records the tree a search walks, for graphviz (`to_dot`) or a browser (`to_html`).
+//
+// the search calls `enter` before descending and `leave` after,
+// `note` attaches the outcome (result, why it was pruned) to the current node.
+// branches that are cut without descending are added with `leaf`.
+// nodes beyond `max_depth`, or after `max_nodes`, aren't recorded.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Inner,
+    Done,
+    Pruned,
+}
+
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    // the decision that led here.
+    pub edge: String,
+    pub state: String,
+    pub note: String,
+    pub kind: Kind,
+}
+
+pub struct SearchTree {
+    pub nodes: Vec<Node>,
+    pub max_depth: usize,
+    pub max_nodes: usize,
+    // nodes that weren't recorded, because of the limits.
+    pub dropped: u64,
+    // `None` for unrecorded nodes.
+    path: Vec<Option<usize>>,
+}
+
+// what a search reports while it runs.
+// solvers are generic over this, so with `NoTrace` the calls compile to nothing.
+pub trait Trace {
+    fn enter(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String);
+    fn leave(&mut self);
+    fn note(&mut self, kind: Kind, note: impl FnOnce() -> String);
+    fn leaf(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String, kind: Kind, note: impl FnOnce() -> String);
+}
+
+pub struct NoTrace;
+
+impl Trace for NoTrace {
+    #[inline(always)]
+    fn enter(&mut self, _: impl FnOnce() -> String, _: impl FnOnce() -> String) {}
+
+    #[inline(always)]
+    fn leave(&mut self) {}
+
+    #[inline(always)]
+    fn note(&mut self, _: Kind, _: impl FnOnce() -> String) {}
+
+    #[inline(always)]
+    fn leaf(&mut self, _: impl FnOnce() -> String, _: impl FnOnce() -> String, _: Kind, _: impl FnOnce() -> String) {}
+}
+
+impl Trace for SearchTree {
+    fn enter(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String) {
+        SearchTree::enter(self, edge, state)
+    }
+
+    fn leave(&mut self) {
+        SearchTree::leave(self)
+    }
+
+    fn note(&mut self, kind: Kind, note: impl FnOnce() -> String) {
+        SearchTree::note(self, kind, note)
+    }
+
+    fn leaf(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String, kind: Kind, note: impl FnOnce() -> String) {
+        SearchTree::leaf(self, edge, state, kind, note)
+    }
+}
+
+impl SearchTree {
+    pub fn new(max_depth: usize, max_nodes: usize) -> Self {
+        SearchTree { nodes: vec![], max_depth, max_nodes, dropped: 0, path: vec![] }
+    }
+
+    fn add(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String, kind: Kind) -> Option<usize> {
+        let parent = match self.path.last() {
+            Some(Some(parent)) => Some(*parent),
+            Some(None) => { self.dropped += 1; return None }
+            None => None,
+        };
+        if self.path.len() > self.max_depth || self.nodes.len() >= self.max_nodes {
+            self.dropped += 1;
+            return None;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            parent,
+            children: vec![],
+            edge: edge(),
+            state: state(),
+            note: String::new(),
+            kind,
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        return Some(id);
+    }
+
+    // the closures only run if the node is recorded.
+    pub fn enter(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String) {
+        let id = self.add(edge, state, Kind::Inner);
+        self.path.push(id);
+    }
+
+    pub fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    pub fn note(&mut self, kind: Kind, note: impl FnOnce() -> String) {
+        if let Some(Some(id)) = self.path.last() {
+            let node = &mut self.nodes[*id];
+            node.kind = kind;
+            node.note = note();
+        }
+    }
+
+    pub fn leaf(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String, kind: Kind, note: impl FnOnce() -> String) {
+        if let Some(id) = self.add(edge, state, kind) {
+            self.nodes[id].note = note();
+        }
+    }
+
+
+    pub fn to_dot(&self) -> String {
+        let mut result = String::from("digraph search {\n    node [shape=box, fontname=monospace];\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let colour = match node.kind {
+                Kind::Inner  => "white",
+                Kind::Done   => "palegreen",
+                Kind::Pruned => "lightpink",
+            };
+            let mut label = node.state.clone();
+            if !node.note.is_empty() {
+                label.push('\n');
+                label.push_str(&node.note);
+            }
+            result.push_str(&format!("    n{id} [label=\"{}\", style=filled, fillcolor={colour}];\n", dot_escape(&label)));
+            if let Some(parent) = node.parent {
+                result.push_str(&format!("    n{parent} -> n{id} [label=\"{}\"];\n", dot_escape(&node.edge)));
+            }
+        }
+        if self.dropped > 0 {
+            result.push_str(&format!("    // {} more nodes not recorded.\n", self.dropped));
+        }
+        result.push_str("}\n");
+        return result;
+    }
+
+    pub fn to_html(&self, title: &str) -> String {
+        let mut result = format!("<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\n", html_escape(title));
+        result.push_str("<style>\n\
+            body { font-family: monospace; }\n\
+            details { margin-left: 1.5em; }\n\
+            .edge { font-weight: bold; }\n\
+            .Done { background: palegreen; }\n\
+            .Pruned { background: lightpink; }\n\
+            </style></head><body>\n");
+        result.push_str(&format!("<h3>{}</h3>\n", html_escape(title)));
+        if self.dropped > 0 {
+            result.push_str(&format!("<p>{} nodes, {} more not recorded.</p>\n", self.nodes.len(), self.dropped));
+        }
+
+        // iterative, the trees can be deep.
+        let mut stack = Vec::from_iter(self.roots().rev().map(|id| (id, false)));
+        while let Some((id, close)) = stack.pop() {
+            if close {
+                result.push_str("</details>\n");
+                continue;
+            }
+
+            let node = &self.nodes[id];
+            let summary = format!("<span class=\"edge\">{}</span> {} <span class=\"{:?}\">{}</span>",
+                html_escape(&node.edge), html_escape(&node.state), node.kind, html_escape(&node.note));
+
+            if node.children.is_empty() {
+                result.push_str(&format!("<div style=\"margin-left: 1.5em\">{summary}</div>\n"));
+            }
+            else {
+                let open = if node.parent.is_none() { " open" } else { "" };
+                result.push_str(&format!("<details{open}><summary>{summary}</summary>\n"));
+                stack.push((id, true));
+                stack.extend(node.children.iter().rev().map(|c| (*c, false)));
+            }
+        }
+
+        result.push_str("</body></html>\n");
+        return result;
+    }
+
+    fn roots(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.nodes.iter().enumerate().filter(|(_, n)| n.parent.is_none()).map(|(id, _)| id)
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
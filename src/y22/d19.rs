@@ -1,5 +1,10 @@
 use std::collections::HashMap;
-use regex::Regex;
+use rayon::prelude::*;
+use crate::util::bits::bit_pack;
+use crate::util::lines;
+use crate::util::parse::Cursor;
+use crate::util::stats;
+use crate::util::tree::{Kind, NoTrace, SearchTree, Trace};
 
 
 #[derive(Clone, Copy, Debug)]
@@ -15,12 +20,11 @@ struct Blueprint {
 fn parse(input: &str) -> Vec<Blueprint> {
     let mut result = Vec::with_capacity(128);
 
-    let re = Regex::new(r"\d+").unwrap();
-    for line in input.lines() {
-        let mut numbers = re.find_iter(line);
+    for line in lines::lines(input) {
+        let mut numbers = Cursor::new(line);
         let mut next = || -> u8 {
-            let number = numbers.next().unwrap();
-            number.as_str().parse().unwrap()
+            assert!(numbers.skip_until_number());
+            numbers.next_u64().unwrap().try_into().unwrap()
         };
 
         let id = next();
@@ -36,23 +40,33 @@ fn parse(input: &str) -> Vec<Blueprint> {
             geode_robot,
             max_ore_cost: ore_robot.max(clay_robot).max(obsi_robot.0).max(geode_robot.0),
         });
-        assert!(numbers.next().is_none());
+        assert!(!numbers.skip_until_number());
     }
 
     result
 }
 
 
+bit_pack! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Pack: u64 {
+        ore_robot:   u8 = 8,
+        clay_robot:  u8 = 8,
+        obsi_robot:  u8 = 8,
+        geode_robot: u8 = 8,
+        ore:   u8 = 8,
+        clay:  u8 = 8,
+        obsi:  u8 = 8,
+        geode: u8 = 8,
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Pack {
-    ore_robot:   u8,
-    clay_robot:  u8,
-    obsi_robot:  u8,
-    geode_robot: u8,
-    ore:   u8,
-    clay:  u8,
-    obsi:  u8,
-    geode: u8,
+enum Robot {
+    Ore,
+    Clay,
+    Obsi,
+    Geode,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -135,6 +149,31 @@ impl State {
         return result;
     }
 
+    fn can_build(&self, robot: Robot, bp: &Blueprint) -> bool {
+        match robot {
+            Robot::Ore   => self.can_build_ore_robot(bp),
+            Robot::Clay  => self.can_build_clay_robot(bp),
+            Robot::Obsi  => self.can_build_obsi_robot(bp),
+            Robot::Geode => self.can_build_geode_robot(bp),
+        }
+    }
+
+    fn build(self, robot: Robot, bp: &Blueprint) -> Self {
+        match robot {
+            Robot::Ore   => self.build_ore_robot(bp),
+            Robot::Clay  => self.build_clay_robot(bp),
+            Robot::Obsi  => self.build_obsi_robot(bp),
+            Robot::Geode => self.build_geode_robot(bp),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let p = &self.pack;
+        format!("minute {:2} | robots {}/{}/{}/{} | ore {}, clay {}, obsi {}, geode {}",
+            self.minute, p.ore_robot, p.clay_robot, p.obsi_robot, p.geode_robot,
+            p.ore, p.clay, p.obsi, p.geode)
+    }
+
     #[inline]
     fn step(self) -> Self {
         let mut this = self;
@@ -148,26 +187,68 @@ impl State {
 }
 
 
-struct Solver {
+struct Solver<T: Trace = NoTrace> {
     bp: Blueprint,
     #[allow(unused)]
     memo: HashMap<u64, (u8, u8)>,
     limit: u8,
     max_result: u8,
+    // decisions on the way to the current state & the best final state.
+    path: Vec<Option<Robot>>,
+    best: Vec<Option<Robot>>,
+    // only records anything for `search_tree`.
+    tree: T,
 }
 
 impl Solver {
     fn new(bp: Blueprint, limit: u8) -> Solver {
+        Solver::with_trace(bp, limit, NoTrace)
+    }
+}
+
+impl<T: Trace> Solver<T> {
+    fn with_trace(bp: Blueprint, limit: u8, tree: T) -> Solver<T> {
         Solver {
             bp,
             memo: HashMap::new(),
             limit,
             max_result: 0,
+            path: Vec::with_capacity(limit as usize),
+            best: vec![],
+            tree,
         }
     }
 
+    fn solve(&mut self) -> Plan {
+        self.rec(State::new(), true, true, true);
+        return Plan::replay(self.bp, &self.best).unwrap();
+    }
+
+    #[inline]
+    fn rec_build(&mut self, state: State, robot: Option<Robot>, can_ore: bool, can_clay: bool, can_obsi: bool) -> u8 {
+        let next = match robot {
+            Some(robot) => state.step().build(robot, &self.bp),
+            None        => state.step(),
+        };
+        self.tree.enter(|| edge_name(robot), || next.describe());
+
+        self.path.push(robot);
+        let result = self.rec(next, can_ore, can_clay, can_obsi);
+        self.path.pop();
+
+        self.tree.leave();
+        return result;
+    }
+
+    // a branch that isn't searched.
+    #[inline]
+    fn skip(&mut self, state: State, robot: Robot, why: impl FnOnce() -> String) {
+        let bp = self.bp;
+        self.tree.leaf(|| edge_name(Some(robot)), || state.step().build(robot, &bp).describe(), Kind::Pruned, why);
+    }
+
     fn rec(&mut self, state: State, can_ore: bool, can_clay: bool, can_obsi: bool) -> u8 {
-        // let pack_64: u64 = unsafe { core::mem::transmute(state.pack) };
+        // let pack_64 = state.pack.to_bits();
 
         // if let Some((minute, result)) = self.memo.get(&pack_64).copied() {
         //     if state.minute >= minute {
@@ -175,9 +256,15 @@ impl Solver {
         //     }
         // }
 
+        stats::with(|s| s.states_visited += 1);
+
         if state.minute == self.limit {
             let result = state.pack.geode;
             //self.memo.insert(pack_64, (state.minute, result));
+            if result > self.max_result || self.best.is_empty() {
+                self.best.clone_from(&self.path);
+            }
+            self.tree.note(Kind::Done, || format!("{result} geodes"));
             self.max_result = self.max_result.max(result);
             return result;
         }
@@ -188,6 +275,15 @@ impl Solver {
             let max_yield = remaining * state.pack.geode_robot as u32 + remaining*(remaining-1)/2;
             if state.pack.geode as u32 + max_yield <= self.max_result as u32 {
                 //self.memo.insert(pack_64, (state.minute, 0));
+                stats::with(|s| s.pruned += 1);
+                let max = self.max_result;
+                self.tree.note(Kind::Pruned, || format!("at most {} geodes, have {max}", state.pack.geode as u32 + max_yield));
+
+                // no geodes at all, idling to the end is as good as anything.
+                if self.best.is_empty() {
+                    self.best.clone_from(&self.path);
+                    self.best.resize(self.limit as usize, None);
+                }
                 return 0;
             }
         }
@@ -195,7 +291,7 @@ impl Solver {
         let mut result = 0;
 
         if state.can_build_geode_robot(&self.bp) {
-            result = result.max(self.rec(state.step().build_geode_robot(&self.bp), true, true, true));
+            result = result.max(self.rec_build(state, Some(Robot::Geode), true, true, true));
         }
         else {
             let mut new_can_obsi = true;
@@ -203,7 +299,10 @@ impl Solver {
                 new_can_obsi = false;
 
                 if can_obsi && state.pack.obsi_robot < self.bp.geode_robot.1 {
-                    result = result.max(self.rec(state.step().build_obsi_robot(&self.bp), true, true, true));
+                    result = result.max(self.rec_build(state, Some(Robot::Obsi), true, true, true));
+                }
+                else {
+                    self.skip(state, Robot::Obsi, || skip_reason(can_obsi));
                 }
             }
 
@@ -212,7 +311,10 @@ impl Solver {
                 new_can_clay = false;
 
                 if can_clay && state.pack.clay_robot < self.bp.obsi_robot.1 {
-                    result = result.max(self.rec(state.step().build_clay_robot(&self.bp), true, true, true));
+                    result = result.max(self.rec_build(state, Some(Robot::Clay), true, true, true));
+                }
+                else {
+                    self.skip(state, Robot::Clay, || skip_reason(can_clay));
                 }
             }
 
@@ -221,12 +323,15 @@ impl Solver {
                 new_can_ore = false;
 
                 if can_ore && state.pack.ore_robot < self.bp.max_ore_cost {
-                    result = result.max(self.rec(state.step().build_ore_robot(&self.bp), true, true, true));
+                    result = result.max(self.rec_build(state, Some(Robot::Ore), true, true, true));
+                }
+                else {
+                    self.skip(state, Robot::Ore, || skip_reason(can_ore));
                 }
             }
 
             // wait & build on next turn.
-            result = result.max(self.rec(state.step(), new_can_ore, new_can_clay, new_can_obsi));
+            result = result.max(self.rec_build(state, None, new_can_ore, new_can_clay, new_can_obsi));
         }
 
         // for some reason, this is equivalent to `self.memo.insert(pack64, (state.minute, result))`
@@ -242,98 +347,417 @@ impl Solver {
 
         return result;
     }
+}
 
-    fn brute_force(&mut self, state: State, n: &mut u32) -> u8 {
-        let pack_64: u64 = unsafe { core::mem::transmute(state.pack) };
 
-        if let Some((minute, result)) = self.memo.get(&pack_64).copied() {
-            if state.minute >= minute {
-                return result;
+fn edge_name(robot: Option<Robot>) -> String {
+    match robot {
+        Some(robot) => format!("build {robot:?}"),
+        None => "wait".into(),
+    }
+}
+
+fn skip_reason(could: bool) -> String {
+    if !could { "could have built it before waiting".into() }
+    else { "enough of these robots".into() }
+}
+
+// the search for one blueprint, for `aoc tree`.
+pub fn search_tree(input: &str, id: u8, limit: u8, max_depth: usize, max_nodes: usize) -> Result<SearchTree, String> {
+    let bp = parse(input).into_iter().find(|bp| bp.id == id)
+        .ok_or_else(|| format!("no blueprint {id}"))?;
+
+    let mut tree = SearchTree::new(max_depth, max_nodes);
+    tree.enter(|| "start".into(), || State::new().describe());
+
+    let mut solver = Solver::with_trace(bp, limit, tree);
+    let geodes = solver.rec(State::new(), true, true, true);
+
+    let mut tree = solver.tree;
+    tree.note(Kind::Inner, || format!("best: {geodes} geodes"));
+    tree.leave();
+    return Ok(tree);
+}
+
+
+// one minute of a plan.
+// `after` is `before.step()`, then the robot is built.
+#[derive(Clone, Copy, Debug)]
+struct Step {
+    robot:  Option<Robot>,
+    before: State,
+    after:  State,
+}
+
+struct Plan {
+    bp: Blueprint,
+    steps: Vec<Step>,
+}
+
+impl Plan {
+    // runs the decisions from `State::new()`,
+    // fails if a robot can't be afforded at that point.
+    fn replay(bp: Blueprint, decisions: &[Option<Robot>]) -> Result<Plan, String> {
+        let mut steps = Vec::with_capacity(decisions.len());
+        let mut state = State::new();
+        for robot in decisions.iter().copied() {
+            let before = state;
+            state = match robot {
+                Some(robot) => {
+                    if !state.can_build(robot, &bp) {
+                        return Err(format!("minute {}: can't afford a {:?} robot", state.minute + 1, robot));
+                    }
+                    state.step().build(robot, &bp)
+                }
+                None => state.step(),
+            };
+            steps.push(Step { robot, before, after: state });
+        }
+        return Ok(Plan { bp, steps });
+    }
+
+    fn decisions(&self) -> Vec<Option<Robot>> {
+        Vec::from_iter(self.steps.iter().map(|s| s.robot))
+    }
+
+    fn end(&self) -> State {
+        self.steps.last().map_or(State::new(), |s| s.after)
+    }
+
+    fn geodes(&self) -> u8 {
+        self.end().pack.geode
+    }
+
+    // minutes without a build are skipped.
+    fn print(&self) {
+        println!("blueprint {}: {} geodes in {} minutes", self.bp.id, self.geodes(), self.steps.len());
+        for step in &self.steps {
+            let Some(robot) = step.robot else { continue };
+            let (b, a) = (step.before.pack, step.after.pack);
+            println!("  minute {:2}: {:5} | ore {:2} -> {:2}, clay {:2} -> {:2}, obsi {:2} -> {:2}, geode {:2} -> {:2}",
+                step.after.minute, format!("{robot:?}"),
+                b.ore, a.ore, b.clay, a.clay, b.obsi, a.obsi, b.geode, a.geode);
+        }
+    }
+}
+
+
+// the same search for any number of resources & any costs.
+// robot `i` produces resource `i`, and the last resource is the one we maximize.
+mod generic {
+    use crate::util::lines;
+    use crate::util::pattern::parse;
+    use crate::util::stats;
+
+    #[derive(Clone, Debug)]
+    pub struct Recipe<const N: usize> {
+        pub id: u32,
+        // `costs[robot][resource]`.
+        pub costs: [[u16; N]; N],
+        // more robots of a kind than any robot costs are useless,
+        // we can only build one per minute.
+        pub max_cost: [u16; N],
+    }
+
+    impl<const N: usize> Recipe<N> {
+        pub const GOAL: usize = N - 1;
+
+        pub fn new(id: u32, costs: [[u16; N]; N]) -> Self {
+            let mut max_cost = [0; N];
+            for robot in &costs {
+                for (max, cost) in max_cost.iter_mut().zip(robot) {
+                    *max = (*max).max(*cost);
+                }
             }
+            return Recipe { id, costs, max_cost };
         }
 
-        if state.minute == self.limit {
-            return state.pack.geode;
+        // "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. ..."
+        // resources are numbered in the order their robots are listed.
+        pub fn parse(line: &str) -> Result<Self, String> {
+            let (id, sentences) = parse!(line, "Blueprint " {u32} ": " {&str}).map_err(|e| e.to_string())?;
+
+            let mut robots = vec![];
+            for sentence in sentences.split('.').map(str::trim).filter(|s| !s.is_empty()) {
+                let (name, costs) = parse!(sentence, "Each " {&str} " robot costs " {&str})
+                    .map_err(|e| format!("{sentence:?}: {e}"))?;
+
+                let mut parsed = vec![];
+                for cost in costs.split(" and ") {
+                    parsed.push(parse!(cost, {u16} " " {&str}).map_err(|e| format!("{cost:?}: {e}"))?);
+                }
+                robots.push((name, parsed));
+            }
+
+            if robots.len() != N {
+                return Err(format!("blueprint {id} has {} robots, expected {N}", robots.len()));
+            }
+
+            let mut costs = [[0; N]; N];
+            for (robot, (_, parsed)) in robots.iter().enumerate() {
+                for (amount, resource) in parsed {
+                    let Some(resource) = robots.iter().position(|(name, _)| name == resource) else {
+                        return Err(format!("blueprint {id}: no {resource:?} robot"));
+                    };
+                    costs[robot][resource] += amount;
+                }
+            }
+
+            return Ok(Recipe::new(id, costs));
         }
+    }
+
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct State<const N: usize> {
+        pub minute: u8,
+        pub robots: [u16; N],
+        pub stock:  [u16; N],
+    }
 
-        *n += 1;
-        if *n % 16*1024 == 0 {
-            //println!("{}", n);
+    impl<const N: usize> State<N> {
+        pub fn new() -> Self {
+            let mut robots = [0; N];
+            robots[0] = 1;
+            State { minute: 0, robots, stock: [0; N] }
         }
 
-        let mut result = 0;
+        #[inline]
+        pub fn can_build(&self, robot: usize, recipe: &Recipe<N>) -> bool {
+            self.stock.iter().zip(&recipe.costs[robot]).all(|(have, cost)| have >= cost)
+        }
 
-        if state.can_build_geode_robot(&self.bp) {
-            result = result.max(self.brute_force(state.step().build_geode_robot(&self.bp), n));
+        #[inline]
+        pub fn build(self, robot: usize, recipe: &Recipe<N>) -> Self {
+            let mut result = self;
+            for (have, cost) in result.stock.iter_mut().zip(&recipe.costs[robot]) {
+                *have -= cost;
+            }
+            result.robots[robot] += 1;
+            return result;
         }
-        else {
-            if state.can_build_obsi_robot(&self.bp) {
-                result = result.max(self.brute_force(state.step().build_obsi_robot(&self.bp), n));
+
+        #[inline]
+        pub fn step(self) -> Self {
+            let mut this = self;
+            this.minute += 1;
+            for (have, robots) in this.stock.iter_mut().zip(&self.robots) {
+                *have += robots;
+            }
+            return this;
+        }
+    }
+
+
+    pub struct Solver<'a, const N: usize> {
+        recipe: &'a Recipe<N>,
+        limit: u8,
+        max_result: u16,
+    }
+
+    impl<'a, const N: usize> Solver<'a, N> {
+        pub fn new(recipe: &'a Recipe<N>, limit: u8) -> Self {
+            Solver { recipe, limit, max_result: 0 }
+        }
+
+        pub fn solve(&mut self) -> u16 {
+            self.rec(State::new(), [true; N])
+        }
+
+        // `can[i]`: false if we could have built robot `i` last minute, but waited.
+        // building it now would've been better then.
+        fn rec(&mut self, state: State<N>, can: [bool; N]) -> u16 {
+            let goal = Recipe::<N>::GOAL;
+
+            stats::with(|s| s.states_visited += 1);
+
+            if state.minute == self.limit {
+                let result = state.stock[goal];
+                self.max_result = self.max_result.max(result);
+                return result;
             }
 
-            if state.can_build_clay_robot(&self.bp) {
-                result = result.max(self.brute_force(state.step().build_clay_robot(&self.bp), n));
+            // can we even beat the max anymore?
+            {
+                let remaining = (self.limit - state.minute) as u32;
+                let max_yield = remaining * state.robots[goal] as u32 + remaining*(remaining-1)/2;
+                if state.stock[goal] as u32 + max_yield <= self.max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+                    return 0;
+                }
             }
 
-            if state.can_build_ore_robot(&self.bp) {
-                result = result.max(self.brute_force(state.step().build_ore_robot(&self.bp), n));
+            let mut result = 0;
+
+            if state.can_build(goal, self.recipe) {
+                result = result.max(self.rec(state.step().build(goal, self.recipe), [true; N]));
             }
+            else {
+                let mut new_can = [true; N];
+                for robot in (0..goal).rev() {
+                    if state.can_build(robot, self.recipe) {
+                        new_can[robot] = false;
+
+                        if can[robot] && state.robots[robot] < self.recipe.max_cost[robot] {
+                            result = result.max(self.rec(state.step().build(robot, self.recipe), [true; N]));
+                        }
+                    }
+                }
 
-            // wait & build on next turn.
-            result = result.max(self.brute_force(state.step(), n));
+                // wait & build on next turn.
+                result = result.max(self.rec(state.step(), new_can));
+            }
+
+            return result;
         }
+    }
+
 
-        self.memo.insert(pack_64, (state.minute, result));
+    pub fn quality_sum<const N: usize>(input: &str, limit: u8) -> u32 {
+        let mut result = 0;
+        for line in lines::lines(input) {
+            let recipe = Recipe::<N>::parse(line).unwrap();
+            result += recipe.id * Solver::new(&recipe, limit).solve() as u32;
+        }
+        return result;
+    }
 
+    pub fn product<const N: usize>(input: &str, limit: u8, count: usize) -> u32 {
+        let mut result = 1;
+        for line in lines::lines(input).take(count) {
+            let recipe = Recipe::<N>::parse(line).unwrap();
+            result *= Solver::new(&recipe, limit).solve() as u32;
+        }
         return result;
     }
 }
 
+fn part_1_generic(input: &str) -> u32 {
+    generic::quality_sum::<4>(input, 24)
+}
+
+fn part_2_generic(input: &str) -> u32 {
+    generic::product::<4>(input, 32, 3)
+}
+
+// a made up recipe with crystals between obsidian and geodes.
+const CRYSTAL_RECIPES: &str = "\
+Blueprint 1: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each crystal robot costs 2 clay and 4 obsidian. Each geode robot costs 3 ore and 6 crystal.
+Blueprint 2: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 2 ore and 10 clay. Each crystal robot costs 3 ore and 3 obsidian. Each geode robot costs 2 obsidian and 5 crystal.
+";
+
 
-fn part_1(input: &str) {
+fn part_1(input: &str) -> u32 {
     let blueprints = parse(input);
 
-    let t0 = std::time::Instant::now();
     let mut result = 0;
     for bp in &blueprints {
         let mut solver = Solver::new(*bp, 24);
         let geodes = solver.rec(State::new(), true, true, true);
-        let mut n = 0;
-        //let geodes = solver.brute_force(State::new(), &mut n);
-        //println!("done {}, visited {} states", geodes, n);
         //println!("{}: {}", bp.id, geodes);
         result += bp.id as u32 * geodes as u32;
     }
-    println!("part 1 result: {} in {:?}", result, t0.elapsed());
+    return result;
 }
 
-fn part_2(input: &str) {
+fn part_2(input: &str) -> u32 {
     let mut blueprints = parse(input);
     if blueprints.len() > 3 {
         blueprints.truncate(3);
     }
 
-    let t0 = std::time::Instant::now();
     let mut result = 1;
     for bp in &blueprints {
         let mut solver = Solver::new(*bp, 32);
         let geodes = solver.rec(State::new(), true, true, true);
-        let mut n = 0;
-        //let geodes = solver.brute_force(State::new(), &mut n);
-        //println!("done {}, visited {} states", geodes, n);
         //println!("{}: {}", bp.id, geodes);
         result *= geodes as u32;
     }
-    println!("part 2 result: {} in {:?}", result, t0.elapsed());
+    return result;
 }
 
-pub fn main() {
-    part_1(include_str!("d19-test.txt"));
-    part_1(include_str!("d19-prod.txt"));
-    part_1(include_str!("d19-prod-2.txt"));
+fn part_1_threaded(input: &str) -> u32 {
+    let blueprints = parse(input);
+    blueprints.par_iter().map(|bp| {
+        let geodes = Solver::new(*bp, 24).rec(State::new(), true, true, true);
+        bp.id as u32 * geodes as u32
+    }).sum()
+}
+
+fn part_2_threaded(input: &str) -> u32 {
+    let blueprints = parse(input);
+    blueprints[..blueprints.len().min(3)].par_iter().map(|bp| {
+        Solver::new(*bp, 32).rec(State::new(), true, true, true) as u32
+    }).product()
+}
+
+// same as `part_1`, but each blueprint's plan is replayed,
+// which checks that it's legal and gets the claimed geodes.
+fn part_1_plans(input: &str) -> u32 {
+    let blueprints = parse(input);
+
+    let mut result = 0;
+    for bp in &blueprints {
+        let geodes = Solver::new(*bp, 24).rec(State::new(), true, true, true);
 
-    part_2(include_str!("d19-test.txt"));
-    part_2(include_str!("d19-prod.txt"));
-    part_2(include_str!("d19-prod-2.txt"));
+        let plan = Solver::new(*bp, 24).solve();
+        let replayed = Plan::replay(*bp, &plan.decisions()).unwrap();
+        assert_eq!(replayed.end(), plan.end());
+        assert_eq!(replayed.end().minute, 24);
+        assert_eq!(plan.geodes(), geodes);
+
+        result += bp.id as u32 * plan.geodes() as u32;
+    }
+    return result;
+}
+
+
+fn run(name: &str, f: impl FnOnce(&str) -> u32, input: &str) {
+    stats::reset();
+    let t0 = std::time::Instant::now();
+    let result = f(input);
+    let dt = t0.elapsed();
+    println!("{name}: {result} in {dt:?}, {:.2} MiB/s",
+        input.len() as f64 / dt.as_secs_f64() / 1024.0 / 1024.0);
+    stats::print();
+}
+
+pub fn main() {
+    println!("-- 2022 day 19 --");
+
+    run("part_1", part_1, include_str!("d19-test.txt"));
+    run("part_1", part_1, include_str!("d19-prod.txt"));
+    run("part_1", part_1, include_str!("d19-prod-2.txt"));
+
+    run("part_2", part_2, include_str!("d19-test.txt"));
+    run("part_2", part_2, include_str!("d19-prod.txt"));
+    run("part_2", part_2, include_str!("d19-prod-2.txt"));
+
+    run("part_1_threaded", part_1_threaded, include_str!("d19-prod.txt"));
+    run("part_1_threaded", part_1_threaded, include_str!("d19-prod-2.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d19-prod.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d19-prod-2.txt"));
+
+    // the speedup of searching one blueprint in parallel.
+    use super::d19_yt::{part_1_search, part_1_search_parallel, part_2_search, part_2_search_parallel};
+    println!("({} threads)", rayon::current_num_threads());
+    run("part_1_search", part_1_search, include_str!("d19-prod.txt"));
+    run("part_1_search_parallel", part_1_search_parallel, include_str!("d19-prod.txt"));
+    run("part_2_search", part_2_search, include_str!("d19-prod.txt"));
+    run("part_2_search_parallel", part_2_search_parallel, include_str!("d19-prod.txt"));
+
+    run("part_1_plans", part_1_plans, include_str!("d19-test.txt"));
+    run("part_1_plans", part_1_plans, include_str!("d19-prod.txt"));
+
+    Solver::new(parse(include_str!("d19-test.txt"))[0], 24).solve().print();
+
+    run("part_1_generic", part_1_generic, include_str!("d19-test.txt"));
+    run("part_1_generic", part_1_generic, include_str!("d19-prod.txt"));
+    run("part_2_generic", part_2_generic, include_str!("d19-test.txt"));
+    run("part_2_generic", part_2_generic, include_str!("d19-prod.txt"));
+    run("crystals 24", |input| generic::quality_sum::<5>(input, 24), CRYSTAL_RECIPES);
+    run("crystals 32", |input| generic::product::<5>(input, 32, 3), CRYSTAL_RECIPES);
+
+    println!();
 }
 
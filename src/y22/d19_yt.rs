@@ -132,58 +132,17 @@
 */
 
 
-use std::{cell::UnsafeCell, hash::Hasher};
+use std::hash::Hasher;
 
-
-struct Stats {
-    memo_refs: u64,
-    memo_hits: u64,
-    states_visited: u64,
-    states_skipped: u128,
-}
-
-impl Stats {
-    #[inline]
-    const fn new() -> Self {
-        Stats { memo_refs: 0, memo_hits: 0, states_visited: 0, states_skipped: 0 }
-    }
-
-    fn reset(&mut self) {
-        *self = Self::new();
-    }
-
-    fn print(&self) {
-        println!("memo refs: {}", self.memo_refs);
-        println!("memo hits: {} ({:.0}%)", self.memo_hits, self.memo_hits as f64 / self.memo_refs as f64 * 100.0);
-        println!("states visited: {}", self.states_visited);
-        println!("states skipped: {}", self.states_skipped);
-        println!();
-    }
-}
-
-struct StatsNonsense {
-    inner: UnsafeCell<Stats>,
-}
-
-impl StatsNonsense {
-    fn with<F: FnOnce(&mut Stats)>(&self, f: F) {
-        if DO_STATS {
-            f(unsafe { &mut *self.inner.get() })
-        }
-    }
-}
-
-unsafe impl Sync for StatsNonsense {}
-
-
-const DO_STATS: bool = 0==1;
-static STATS: StatsNonsense = StatsNonsense { inner: UnsafeCell::new(Stats::new()) };
+use crate::util::bits::bit_pack;
+use crate::util::stats;
 
 
 mod baseline {
-    use regex::Regex;
+    use crate::util::lines;
+    use crate::util::parse::Cursor;
 
-    use super::STATS;
+    use super::stats;
 
     #[derive(Clone, Copy, Debug)]
     pub struct Blueprint {
@@ -197,12 +156,11 @@ mod baseline {
     pub fn parse(input: &str) -> Vec<Blueprint> {
         let mut result = Vec::with_capacity(128);
 
-        let re = Regex::new(r"\d+").unwrap();
-        for line in input.lines() {
-            let mut numbers = re.find_iter(line);
+        for line in lines::lines(input) {
+            let mut numbers = Cursor::new(line);
             let mut next = || -> u32 {
-                let number = numbers.next().unwrap();
-                number.as_str().parse().unwrap()
+                assert!(numbers.skip_until_number());
+                numbers.next_u64().unwrap().try_into().unwrap()
             };
 
             let id = next();
@@ -217,7 +175,7 @@ mod baseline {
                 obsidian_robot,
                 geode_robot,
             });
-            assert!(numbers.next().is_none());
+            assert!(!numbers.skip_until_number());
         }
 
         result
@@ -324,6 +282,7 @@ mod baseline {
     pub mod v1 {
         use super::{Blueprint, State};
 
+        #[allow(dead_code)]
         pub fn solve(bp: &Blueprint, limit: u8) -> u32 {
             let mut state = State::new();
             for _ in 0..limit {
@@ -349,11 +308,12 @@ mod baseline {
     }
 
     pub mod v2 {
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
+        #[allow(dead_code)]
         fn solution(state: State, bp: &Blueprint, limit: u8) -> u32 {
-            STATS.with(|s| { s.states_visited += 1 });
-            STATS.with(|s| { 
+            stats::with(|s| { s.states_visited += 1 });
+            stats::with(|s| { 
                 if s.states_visited % (128*1024*1024) == 0 {
                     println!("{}", s.states_visited);
                 }
@@ -386,6 +346,7 @@ mod baseline {
             return result;
         }
 
+        #[allow(dead_code)]
         pub fn solve(bp: &Blueprint, limit: u8) -> u32 {
             solution(State::new(), bp, limit)
         }
@@ -395,16 +356,16 @@ mod baseline {
         use std::collections::HashMap;
         //use fnv::FnvHashMap as HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<State, u32>) -> u32 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
             if let Some(result) = memo.get(&state).copied() {
-                STATS.with(|s| { s.memo_hits += 1 });
+                stats::with(|s| { s.memo_hits += 1 });
                 return result;
             }
 
@@ -448,16 +409,17 @@ mod baseline {
     pub mod survivor {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
+        #[allow(dead_code)]
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<State, (u32, u128)>) -> (u32, u128) {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
             if let Some(result) = memo.get(&state).copied() {
-                STATS.with(|s| {
+                stats::with(|s| {
                     s.memo_hits += 1;
                     s.states_skipped += result.1;
                 });
@@ -508,6 +470,7 @@ mod baseline {
             return (result, children);
         }
 
+        #[allow(dead_code)]
         pub fn solve(bp: &Blueprint, limit: u8) -> u32 {
             let mut memo = HashMap::new();
             let (r, c) = solution(State::new(), bp, limit, &mut memo);
@@ -517,75 +480,104 @@ mod baseline {
     }
 
     pub mod printer {
-        use super::{STATS, Blueprint, State};
-
-        fn solution(state: State, bp: &Blueprint, limit: u8) {
-            println!("{:?}", state);
+        use super::{Blueprint, State};
+        use crate::util::tree::{Kind, SearchTree, Trace};
 
+        // every plan, nothing is pruned.
+        fn solution<T: Trace>(state: State, bp: &Blueprint, limit: u8, trace: &mut T) {
             if state.minute == limit {
+                trace.note(Kind::Done, || format!("{} geodes", state.geode));
                 return;
             }
 
-            #[inline]
-            fn indent(state: State) {
-                for _ in 0..state.minute + 1 {
-                    print!("  ");
-                }
+            fn branch<T: Trace>(name: &'static str, next: State, bp: &Blueprint, limit: u8, trace: &mut T) {
+                trace.enter(|| name.into(), || format!("{:?}", next));
+                solution(next, bp, limit, trace);
+                trace.leave();
             }
 
             if state.can_build_geode_robot(bp) {
-                indent(state);
-                print!("geode ");
-                solution(state.step().build_geode_robot(bp), bp, limit);
+                branch("geode", state.step().build_geode_robot(bp), bp, limit, trace);
             }
 
             if state.can_build_obsidian_robot(bp) {
-                indent(state);
-                print!("obsidian ");
-                solution(state.step().build_obsidian_robot(bp), bp, limit);
+                branch("obsidian", state.step().build_obsidian_robot(bp), bp, limit, trace);
             }
 
             if state.can_build_clay_robot(bp) {
-                indent(state);
-                print!("clay ");
-                solution(state.step().build_clay_robot(bp), bp, limit);
+                branch("clay", state.step().build_clay_robot(bp), bp, limit, trace);
             }
 
             if state.can_build_ore_robot(bp) {
-                indent(state);
-                print!("ore ");
-                solution(state.step().build_ore_robot(bp), bp, limit);
+                branch("ore", state.step().build_ore_robot(bp), bp, limit, trace);
             }
 
-            indent(state);
-            print!("wait ");
-            solution(state.step(), bp, limit);
+            branch("wait", state.step(), bp, limit, trace);
+        }
+
+        // prints the tree as indented text.
+        struct Indent {
+            depth: usize,
+        }
+
+        impl Indent {
+            fn line(&self, edge: String, state: String) {
+                println!("{}{edge} {state}", "  ".repeat(self.depth));
+            }
         }
 
+        impl Trace for Indent {
+            fn enter(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String) {
+                self.line(edge(), state());
+                self.depth += 1;
+            }
+
+            fn leave(&mut self) {
+                self.depth -= 1;
+            }
+
+            fn note(&mut self, _: Kind, _: impl FnOnce() -> String) {}
+
+            fn leaf(&mut self, edge: impl FnOnce() -> String, state: impl FnOnce() -> String, _: Kind, _: impl FnOnce() -> String) {
+                self.line(edge(), state());
+            }
+        }
+
+        #[allow(dead_code)]
         pub fn tree(bp: &Blueprint, limit: u8) {
-            solution(State::new(), bp, limit);
+            println!("{:?}", State::new());
+            solution(State::new(), bp, limit, &mut Indent { depth: 1 });
+        }
+
+        pub fn search_tree(bp: &Blueprint, limit: u8, max_depth: usize, max_nodes: usize) -> SearchTree {
+            let mut tree = SearchTree::new(max_depth, max_nodes);
+            tree.enter(|| "start".into(), || format!("{:?}", State::new()));
+            solution(State::new(), bp, limit, &mut tree);
+            tree.leave();
+            return tree;
         }
     }
 
     pub fn part_1<F: Fn(&Blueprint, u8) -> u32>(bps: &[Blueprint], f: F) {
-        super::STATS.with(|s| s.reset());
+        stats::reset();
         let t0 = std::time::Instant::now();
         let mut result = 0;
         for bp in bps {
             let t0 = std::time::Instant::now();
             let geodes = f(bp, 24);
             println!("geodes: {} in {:?}", geodes, t0.elapsed());
-            result += bp.id as u32 * geodes as u32;
+            result += bp.id * geodes;
         }
         println!("part 1 result: {} in {:?}", result, t0.elapsed());
-        super::STATS.with(|s| s.print());
+        stats::print();
+        println!();
     }
 }
 
 mod pack {
     pub use super::baseline::{Blueprint, parse, part_1};
 
-    use super::STATS;
+    use super::stats;
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
     struct Pack {
@@ -694,19 +686,19 @@ mod pack {
     pub mod v1 {
         use std::collections::HashMap;
 
-        use super::STATS;
+        use super::stats;
 
         use super::{Blueprint, Pack, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<Pack, (u32, u8)>) -> u32 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
             if let Some((result, minute)) = memo.get(&state.pack).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
 
                     return result;
                 }
@@ -765,6 +757,7 @@ mod pack {
             solution(State::new(), bp, limit, &mut memo)
         }
 
+        #[allow(dead_code)]
         pub fn solve_stats(bp: &Blueprint, limit: u8) -> u32 {
             let mut memo = HashMap::new();
             let result = solution(State::new(), bp, limit, &mut memo);
@@ -791,9 +784,10 @@ mod pack {
 }
 
 mod pack_u8 {
-    use regex::Regex;
+    use crate::util::lines;
+    use crate::util::parse::Cursor;
 
-    use super::STATS;
+    use super::{stats, bit_pack};
 
     #[derive(Clone, Copy, Debug)]
     pub struct Blueprint {
@@ -807,12 +801,11 @@ mod pack_u8 {
     pub fn parse(input: &str) -> Vec<Blueprint> {
         let mut result = Vec::with_capacity(128);
 
-        let re = Regex::new(r"\d+").unwrap();
-        for line in input.lines() {
-            let mut numbers = re.find_iter(line);
+        for line in lines::lines(input) {
+            let mut numbers = Cursor::new(line);
             let mut next = || -> u8 {
-                let number = numbers.next().unwrap();
-                number.as_str().parse().unwrap()
+                assert!(numbers.skip_until_number());
+                numbers.next_u64().unwrap().try_into().unwrap()
             };
 
             let id = next();
@@ -827,23 +820,25 @@ mod pack_u8 {
                 obsidian_robot,
                 geode_robot,
             });
-            assert!(numbers.next().is_none());
+            assert!(!numbers.skip_until_number());
         }
 
         result
     }
 
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-    struct Pack {
-        pub ore_robot:      u8,
-        pub clay_robot:     u8,
-        pub obsidian_robot: u8,
-        pub geode_robot:    u8,
-        pub ore:      u8,
-        pub clay:     u8,
-        pub obsidian: u8,
-        pub geode:    u8,
+    bit_pack! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        struct Pack: u64 {
+            pub ore_robot:      u8 = 8,
+            pub clay_robot:     u8 = 8,
+            pub obsidian_robot: u8 = 8,
+            pub geode_robot:    u8 = 8,
+            pub ore:      u8 = 8,
+            pub clay:     u8 = 8,
+            pub obsidian: u8 = 8,
+            pub geode:    u8 = 8,
+        }
     }
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -941,17 +936,17 @@ mod pack_u8 {
     pub mod v1 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, Pack, State};
+        use super::{stats, Blueprint, Pack, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<Pack, (u8, u8)>) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
             if let Some((result, minute)) = memo.get(&state.pack).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
                     return result;
                 }
             }
@@ -1013,19 +1008,19 @@ mod pack_u8 {
     pub mod v2 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<u64, (u8, u8)>) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
-            let pack_64 = unsafe { core::mem::transmute(state.pack) };
+            let pack_64 = state.pack.to_bits();
 
             if let Some((result, minute)) = memo.get(&pack_64).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
                     return result;
                 }
             }
@@ -1037,7 +1032,7 @@ mod pack_u8 {
                 //  could be, cause we're writing the results on the way up (going backwards in time).
                 //  but not sure how that would work with "sibling branches".
                 //  in any case, this isn't measurably slower, so who cares.
-                memo.entry(unsafe { core::mem::transmute(state.pack) })
+                memo.entry(state.pack.to_bits())
                 .and_modify(|(old_result, old_minute)| {
                     if state.minute < *old_minute {
                         *old_result = result;
@@ -1085,7 +1080,7 @@ mod pack_u8 {
     }
 
     pub fn part_1<F: Fn(&Blueprint, u8) -> u8>(bps: &[Blueprint], f: F) {
-        super::STATS.with(|s| s.reset());
+        stats::reset();
         let t0 = std::time::Instant::now();
         let mut result = 0;
         for bp in bps {
@@ -1094,14 +1089,16 @@ mod pack_u8 {
             result += bp.id as u32 * geodes as u32;
         }
         println!("part 1 result: {} in {:?}", result, t0.elapsed());
-        super::STATS.with(|s| s.print());
+        stats::print();
+        println!();
     }
 }
 
 mod thonk {
-    use regex::Regex;
+    use crate::util::lines;
+    use crate::util::parse::Cursor;
 
-    use super::STATS;
+    use super::{stats, bit_pack};
 
     #[derive(Clone, Copy, Debug)]
     pub struct Blueprint {
@@ -1133,12 +1130,11 @@ mod thonk {
     pub fn parse(input: &str) -> Vec<Blueprint> {
         let mut result = Vec::with_capacity(128);
 
-        let re = Regex::new(r"\d+").unwrap();
-        for line in input.lines() {
-            let mut numbers = re.find_iter(line);
+        for line in lines::lines(input) {
+            let mut numbers = Cursor::new(line);
             let mut next = || -> u8 {
-                let number = numbers.next().unwrap();
-                number.as_str().parse().unwrap()
+                assert!(numbers.skip_until_number());
+                numbers.next_u64().unwrap().try_into().unwrap()
             };
 
             let id = next();
@@ -1154,23 +1150,25 @@ mod thonk {
                 geode_robot,
                 max_ore_cost: ore_robot.max(clay_robot).max(obsidian_robot.0).max(geode_robot.0),
             });
-            assert!(numbers.next().is_none());
+            assert!(!numbers.skip_until_number());
         }
 
         result
     }
 
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-    struct Pack {
-        pub ore_robot:      u8,
-        pub clay_robot:     u8,
-        pub obsidian_robot: u8,
-        pub geode_robot:    u8,
-        pub ore:      u8,
-        pub clay:     u8,
-        pub obsidian: u8,
-        pub geode:    u8,
+    bit_pack! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        struct Pack: u64 {
+            pub ore_robot:      u8 = 8,
+            pub clay_robot:     u8 = 8,
+            pub obsidian_robot: u8 = 8,
+            pub geode_robot:    u8 = 8,
+            pub ore:      u8 = 8,
+            pub clay:     u8 = 8,
+            pub obsidian: u8 = 8,
+            pub geode:    u8 = 8,
+        }
     }
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -1265,22 +1263,37 @@ mod thonk {
     }
 
 
+    // the robots we may build before the next build.
+    // after waiting, building a robot we could already afford is never better.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Can {
+        pub ore: bool,
+        pub clay: bool,
+        pub obsidian: bool,
+    }
+
+    impl Can {
+        pub const ALL: Can = Can { ore: true, clay: true, obsidian: true };
+    }
+
+
     pub mod v1 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
+        #[allow(dead_code)]
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<u64, (u8, u8)>, max_result: &mut u8) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
-            let pack_64 = unsafe { core::mem::transmute(state.pack) };
+            let pack_64 = state.pack.to_bits();
 
             if let Some((result, minute)) = memo.get(&pack_64).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
 
                     *max_result = (*max_result).max(result);
                     return result;
@@ -1294,7 +1307,7 @@ mod thonk {
                 //  could be, cause we're writing the results on the way up (going backwards in time).
                 //  but not sure how that would work with "sibling branches".
                 //  in any case, this isn't measurably slower, so who cares.
-                memo.entry(unsafe { core::mem::transmute(state.pack) })
+                memo.entry(state.pack.to_bits())
                 .and_modify(|(old_result, old_minute)| {
                     if state.minute < *old_minute {
                         *old_result = result;
@@ -1325,6 +1338,8 @@ mod thonk {
                     + remaining*(remaining-1)/2;
 
                 if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+
                     // doesn't matter what we insert,
                     // we already have a better result.
                     insert(memo, &state, 0);
@@ -1357,6 +1372,7 @@ mod thonk {
             return result;
         }
 
+        #[allow(dead_code)]
         pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
             let mut memo = HashMap::new();
             let mut max_result = 0;
@@ -1367,19 +1383,19 @@ mod thonk {
     pub mod v2 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<u64, (u8, u8)>, max_result: &mut u8) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
-            let pack_64 = unsafe { core::mem::transmute(state.pack) };
+            let pack_64 = state.pack.to_bits();
 
             if let Some((result, minute)) = memo.get(&pack_64).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
 
                     *max_result = (*max_result).max(result);
                     return result;
@@ -1393,7 +1409,7 @@ mod thonk {
                 //  could be, cause we're writing the results on the way up (going backwards in time).
                 //  but not sure how that would work with "sibling branches".
                 //  in any case, this isn't measurably slower, so who cares.
-                memo.entry(unsafe { core::mem::transmute(state.pack) })
+                memo.entry(state.pack.to_bits())
                 .and_modify(|(old_result, old_minute)| {
                     if state.minute < *old_minute {
                         *old_result = result;
@@ -1424,6 +1440,8 @@ mod thonk {
                     + remaining*(remaining-1)/2;
 
                 if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+
                     // doesn't matter what we insert,
                     // we already have a better result.
                     insert(memo, &state, 0);
@@ -1475,21 +1493,21 @@ mod thonk {
     pub mod v3 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, Can, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<u64, (u8, u8)>, max_result: &mut u8,
-            can_ore: bool, can_clay: bool, can_obsidian: bool
+            can: Can
         ) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
-            let pack_64 = unsafe { core::mem::transmute(state.pack) };
+            let pack_64 = state.pack.to_bits();
 
             if let Some((result, minute)) = memo.get(&pack_64).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
 
                     *max_result = (*max_result).max(result);
                     return result;
@@ -1503,7 +1521,7 @@ mod thonk {
                 //  could be, cause we're writing the results on the way up (going backwards in time).
                 //  but not sure how that would work with "sibling branches".
                 //  in any case, this isn't measurably slower, so who cares.
-                memo.entry(unsafe { core::mem::transmute(state.pack) })
+                memo.entry(state.pack.to_bits())
                 .and_modify(|(old_result, old_minute)| {
                     if state.minute < *old_minute {
                         *old_result = result;
@@ -1534,6 +1552,8 @@ mod thonk {
                     + remaining*(remaining-1)/2;
 
                 if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+
                     // doesn't matter what we insert,
                     // we already have a better result.
                     insert(memo, &state, 0);
@@ -1544,40 +1564,38 @@ mod thonk {
             let mut result = 0;
 
             if state.can_build_geode_robot(bp) {
-                result = result.max(solution(state.step().build_geode_robot(bp), bp, limit, memo, max_result, true, true, true));
+                result = result.max(solution(state.step().build_geode_robot(bp), bp, limit, memo, max_result, Can::ALL));
             }
 
-            let mut new_can_obsidian = true;
+            let mut next_can = Can::ALL;
             if state.can_build_obsidian_robot(bp) {
-                new_can_obsidian = false;
+                next_can.obsidian = false;
 
                 // can only build one bot per turn.
                 // don't need more bots, if we're producing enough,
                 // so we can build the most expensive bot on each turn.
-                if can_obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
-                    result = result.max(solution(state.step().build_obsidian_robot(bp), bp, limit, memo, max_result, true, true, true));
+                if can.obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
+                    result = result.max(solution(state.step().build_obsidian_robot(bp), bp, limit, memo, max_result, Can::ALL));
                 }
             }
 
-            let mut new_can_clay = true;
             if state.can_build_clay_robot(bp) {
-                new_can_clay = false;
+                next_can.clay = false;
 
-                if can_clay && state.pack.clay_robot < bp.max_clay_cost() {
-                    result = result.max(solution(state.step().build_clay_robot(bp), bp, limit, memo, max_result, true, true, true));
+                if can.clay && state.pack.clay_robot < bp.max_clay_cost() {
+                    result = result.max(solution(state.step().build_clay_robot(bp), bp, limit, memo, max_result, Can::ALL));
                 }
             }
 
-            let mut new_can_ore = true;
             if state.can_build_ore_robot(bp) {
-                new_can_ore = false;
+                next_can.ore = false;
 
-                if can_ore && state.pack.ore_robot < bp.max_ore_cost() {
-                    result = result.max(solution(state.step().build_ore_robot(bp), bp, limit, memo, max_result, true, true, true));
+                if can.ore && state.pack.ore_robot < bp.max_ore_cost() {
+                    result = result.max(solution(state.step().build_ore_robot(bp), bp, limit, memo, max_result, Can::ALL));
                 }
             }
 
-            result = result.max(solution(state.step(), bp, limit, memo, max_result, new_can_ore, new_can_clay, new_can_obsidian));
+            result = result.max(solution(state.step(), bp, limit, memo, max_result, next_can));
 
             insert(memo, &state, result);
 
@@ -1587,28 +1605,28 @@ mod thonk {
         pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
             let mut memo = HashMap::new();
             let mut max_result = 0;
-            solution(State::new(), bp, limit, &mut memo, &mut max_result, true, true, true)
+            solution(State::new(), bp, limit, &mut memo, &mut max_result, Can::ALL)
         }
     }
 
     pub mod v4 {
         use std::collections::HashMap;
 
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, Can, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut HashMap<u64, (u8, u8)>, max_result: &mut u8,
-            can_ore: bool, can_clay: bool, can_obsidian: bool
+            can: Can
         ) -> u8 {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.memo_refs += 1;
                 s.states_visited += 1;
             });
 
-            let pack_64 = unsafe { core::mem::transmute(state.pack) };
+            let pack_64 = state.pack.to_bits();
 
             if let Some((result, minute)) = memo.get(&pack_64).copied() {
                 if state.minute >= minute {
-                    STATS.with(|s| { s.memo_hits += 1 });
+                    stats::with(|s| { s.memo_hits += 1 });
 
                     *max_result = (*max_result).max(result);
                     return result;
@@ -1622,7 +1640,7 @@ mod thonk {
                 //  could be, cause we're writing the results on the way up (going backwards in time).
                 //  but not sure how that would work with "sibling branches".
                 //  in any case, this isn't measurably slower, so who cares.
-                memo.entry(unsafe { core::mem::transmute(state.pack) })
+                memo.entry(state.pack.to_bits())
                 .and_modify(|(old_result, old_minute)| {
                     if state.minute < *old_minute {
                         *old_result = result;
@@ -1653,6 +1671,8 @@ mod thonk {
                     + remaining*(remaining-1)/2;
 
                 if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+
                     // doesn't matter what we insert,
                     // we already have a better result.
                     insert(memo, &state, 0);
@@ -1665,40 +1685,38 @@ mod thonk {
             // building a geode bot is the best thing we can do.
             // the proof is left as an exercise for the reader :P
             if state.can_build_geode_robot(bp) {
-                result = result.max(solution(state.step().build_geode_robot(bp), bp, limit, memo, max_result, true, true, true));
+                result = result.max(solution(state.step().build_geode_robot(bp), bp, limit, memo, max_result, Can::ALL));
             }
             else {
-                let mut new_can_obsidian = true;
+                let mut next_can = Can::ALL;
                 if state.can_build_obsidian_robot(bp) {
-                    new_can_obsidian = false;
+                    next_can.obsidian = false;
 
                     // can only build one bot per turn.
                     // don't need more bots, if we're producing enough,
                     // so we can build the most expensive bot on each turn.
-                    if can_obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
-                        result = result.max(solution(state.step().build_obsidian_robot(bp), bp, limit, memo, max_result, true, true, true));
+                    if can.obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
+                        result = result.max(solution(state.step().build_obsidian_robot(bp), bp, limit, memo, max_result, Can::ALL));
                     }
                 }
 
-                let mut new_can_clay = true;
                 if state.can_build_clay_robot(bp) {
-                    new_can_clay = false;
+                    next_can.clay = false;
 
-                    if can_clay && state.pack.clay_robot < bp.max_clay_cost() {
-                        result = result.max(solution(state.step().build_clay_robot(bp), bp, limit, memo, max_result, true, true, true));
+                    if can.clay && state.pack.clay_robot < bp.max_clay_cost() {
+                        result = result.max(solution(state.step().build_clay_robot(bp), bp, limit, memo, max_result, Can::ALL));
                     }
                 }
 
-                let mut new_can_ore = true;
                 if state.can_build_ore_robot(bp) {
-                    new_can_ore = false;
+                    next_can.ore = false;
 
-                    if can_ore && state.pack.ore_robot < bp.max_ore_cost() {
-                        result = result.max(solution(state.step().build_ore_robot(bp), bp, limit, memo, max_result, true, true, true));
+                    if can.ore && state.pack.ore_robot < bp.max_ore_cost() {
+                        result = result.max(solution(state.step().build_ore_robot(bp), bp, limit, memo, max_result, Can::ALL));
                     }
                 }
 
-                result = result.max(solution(state.step(), bp, limit, memo, max_result, new_can_ore, new_can_clay, new_can_obsidian));
+                result = result.max(solution(state.step(), bp, limit, memo, max_result, next_can));
             }
 
             insert(memo, &state, result);
@@ -1709,17 +1727,17 @@ mod thonk {
         pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
             let mut memo = HashMap::new();
             let mut max_result = 0;
-            solution(State::new(), bp, limit, &mut memo, &mut max_result, true, true, true)
+            solution(State::new(), bp, limit, &mut memo, &mut max_result, Can::ALL)
         }
     }
 
     pub mod v5 {
-        use super::{STATS, Blueprint, State};
+        use super::{stats, Blueprint, Can, State};
 
         fn solution(state: State, bp: &Blueprint, limit: u8, max_result: &mut u8,
-            can_ore: bool, can_clay: bool, can_obsidian: bool
+            can: Can
         ) {
-            STATS.with(|s| {
+            stats::with(|s| {
                 s.states_visited += 1;
             });
 
@@ -1743,6 +1761,7 @@ mod thonk {
                     + remaining*(remaining-1)/2;
 
                 if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
                     return;
                 }
             }
@@ -1750,52 +1769,431 @@ mod thonk {
             // building a geode bot is the best thing we can do.
             // the proof is left as an exercise for the reader :P
             if state.can_build_geode_robot(bp) {
-                solution(state.step().build_geode_robot(bp), bp, limit, max_result, true, true, true);
+                solution(state.step().build_geode_robot(bp), bp, limit, max_result, Can::ALL);
             }
             else {
-                let mut new_can_obsidian = true;
+                let mut next_can = Can::ALL;
                 if state.can_build_obsidian_robot(bp) {
-                    new_can_obsidian = false;
+                    next_can.obsidian = false;
 
                     // can only build one bot per turn.
                     // don't need more bots, if we're producing enough,
                     // so we can build the most expensive bot on each turn.
-                    if can_obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
-                        solution(state.step().build_obsidian_robot(bp), bp, limit, max_result, true, true, true);
+                    if can.obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
+                        solution(state.step().build_obsidian_robot(bp), bp, limit, max_result, Can::ALL);
                     }
                 }
 
-                let mut new_can_clay = true;
                 if state.can_build_clay_robot(bp) {
-                    new_can_clay = false;
+                    next_can.clay = false;
 
-                    if can_clay && state.pack.clay_robot < bp.max_clay_cost() {
-                        solution(state.step().build_clay_robot(bp), bp, limit, max_result, true, true, true);
+                    if can.clay && state.pack.clay_robot < bp.max_clay_cost() {
+                        solution(state.step().build_clay_robot(bp), bp, limit, max_result, Can::ALL);
                     }
                 }
 
-                let mut new_can_ore = true;
                 if state.can_build_ore_robot(bp) {
-                    new_can_ore = false;
+                    next_can.ore = false;
 
-                    if can_ore && state.pack.ore_robot < bp.max_ore_cost() {
-                        solution(state.step().build_ore_robot(bp), bp, limit, max_result, true, true, true);
+                    if can.ore && state.pack.ore_robot < bp.max_ore_cost() {
+                        solution(state.step().build_ore_robot(bp), bp, limit, max_result, Can::ALL);
                     }
                 }
 
-                solution(state.step(), bp, limit, max_result, new_can_ore, new_can_clay, new_can_obsidian);
+                solution(state.step(), bp, limit, max_result, next_can);
             }
         }
 
         pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
             let mut max_result = 0;
-            solution(State::new(), bp, limit, &mut max_result, true, true, true);
+            solution(State::new(), bp, limit, &mut max_result, Can::ALL);
             max_result
         }
     }
 
+    // instead of deciding every minute whether to wait,
+    // pick the next robot and jump to the minute it's built.
+    pub mod v7 {
+        use super::{stats, Blueprint, State};
+
+        // minutes until we have `cost`,
+        // none if nothing produces that resource yet.
+        #[inline]
+        fn wait_for(have: u8, robots: u8, cost: u8) -> Option<u8> {
+            if have >= cost {
+                return Some(0);
+            }
+            if robots == 0 {
+                return None;
+            }
+            Some((cost - have).div_ceil(robots))
+        }
+
+        #[inline]
+        fn wait_for_2(a: Option<u8>, b: Option<u8>) -> Option<u8> {
+            Some(a?.max(b?))
+        }
+
+        // wait, then step into the minute of the build.
+        // none if the robot wouldn't be done before the end.
+        #[inline]
+        fn skip(state: State, limit: u8, wait: Option<u8>) -> Option<State> {
+            let wait = wait?;
+            if state.minute + wait + 1 >= limit {
+                return None;
+            }
+
+            let mut state = state;
+            for _ in 0..wait + 1 {
+                state = state.step();
+            }
+            Some(state)
+        }
+
+        // the old bound: one new geode bot on every remaining turn.
+        pub(super) fn triangle_bound(state: &State, _bp: &Blueprint, limit: u8) -> u32 {
+            let remaining = (limit - state.minute) as u32;
+            state.pack.geode as u32
+            + remaining * state.pack.geode_robot as u32
+            + remaining*(remaining-1)/2
+        }
+
+        pub(super) fn search(state: State, bp: &Blueprint, limit: u8, max_result: &mut u8,
+            bound: fn(&State, &Blueprint, u8) -> u32
+        ) {
+            stats::with(|s| {
+                s.states_visited += 1;
+            });
+
+            // we can always idle until the end.
+            {
+                let remaining = limit - state.minute;
+                let idle = state.pack.geode + remaining * state.pack.geode_robot;
+                *max_result = (*max_result).max(idle);
+            }
+
+            // can we even beat max_result anymore?
+            if bound(&state, bp, limit) <= *max_result as u32 {
+                stats::with(|s| s.pruned += 1);
+                return;
+            }
+
+            let p = &state.pack;
+
+            let wait = wait_for_2(
+                wait_for(p.ore,      p.ore_robot,      bp.geode_robot.0),
+                wait_for(p.obsidian, p.obsidian_robot, bp.geode_robot.1));
+            if let Some(next) = skip(state, limit, wait) {
+                search(next.build_geode_robot(bp), bp, limit, max_result, bound);
+            }
+
+            if p.obsidian_robot < bp.max_obsidian_cost() {
+                let wait = wait_for_2(
+                    wait_for(p.ore,  p.ore_robot,  bp.obsidian_robot.0),
+                    wait_for(p.clay, p.clay_robot, bp.obsidian_robot.1));
+                if let Some(next) = skip(state, limit, wait) {
+                    search(next.build_obsidian_robot(bp), bp, limit, max_result, bound);
+                }
+            }
+
+            if p.clay_robot < bp.max_clay_cost() {
+                let wait = wait_for(p.ore, p.ore_robot, bp.clay_robot);
+                if let Some(next) = skip(state, limit, wait) {
+                    search(next.build_clay_robot(bp), bp, limit, max_result, bound);
+                }
+            }
+
+            if p.ore_robot < bp.max_ore_cost() {
+                let wait = wait_for(p.ore, p.ore_robot, bp.ore_robot);
+                if let Some(next) = skip(state, limit, wait) {
+                    search(next.build_ore_robot(bp), bp, limit, max_result, bound);
+                }
+            }
+        }
+
+        pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
+            let mut max_result = 0;
+            search(State::new(), bp, limit, &mut max_result, triangle_bound);
+            max_result
+        }
+    }
+
+    // v7 with a tighter bound from a relaxed game:
+    // ore is free, we get a clay bot every turn, and obsidian & geode bots
+    // are built as soon as their clay & obsidian cost is there,
+    // in the same turn, from separate budgets.
+    pub mod v8 {
+        use super::{Blueprint, State};
+
+        pub(super) fn relaxed_bound(state: &State, bp: &Blueprint, limit: u8) -> u32 {
+            let p = &state.pack;
+            let (mut clay, mut obsidian, mut geode) = (p.clay as u32, p.obsidian as u32, p.geode as u32);
+            let (mut clay_bots, mut obsidian_bots, mut geode_bots) =
+                (p.clay_robot as u32, p.obsidian_robot as u32, p.geode_robot as u32);
+
+            let obsidian_cost = bp.obsidian_robot.1 as u32;
+            let geode_cost    = bp.geode_robot.1 as u32;
+
+            for _ in state.minute..limit {
+                let new_obsidian_bot = clay >= obsidian_cost;
+                let new_geode_bot    = obsidian >= geode_cost;
+                if new_obsidian_bot { clay     -= obsidian_cost }
+                if new_geode_bot    { obsidian -= geode_cost }
+
+                clay     += clay_bots;
+                obsidian += obsidian_bots;
+                geode    += geode_bots;
+
+                clay_bots += 1;
+                obsidian_bots += new_obsidian_bot as u32;
+                geode_bots    += new_geode_bot as u32;
+            }
+            return geode;
+        }
+
+        pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
+            let mut max_result = 0;
+            super::v7::search(State::new(), bp, limit, &mut max_result, relaxed_bound);
+            max_result
+        }
+    }
+
+    // v4 with the fixed size `MemoTable`.
+    // entries for earlier minutes win, they save more work.
+    pub mod v9 {
+        use crate::util::memo::{MemoTable, Replace, Fx};
+
+        use super::{stats, Blueprint, Can, State};
+
+        type Memo = MemoTable<(u8, u8), Fx>;
+
+        fn solution(state: State, bp: &Blueprint, limit: u8, memo: &mut Memo, max_result: &mut u8,
+            can: Can
+        ) -> u8 {
+            stats::with(|s| s.states_visited += 1);
+
+            let pack_64 = state.pack.to_bits();
+
+            if let Some((result, minute)) = memo.get(pack_64) {
+                if state.minute >= minute {
+                    *max_result = (*max_result).max(result);
+                    return result;
+                }
+            }
+
+            #[inline]
+            fn insert(memo: &mut Memo, state: &State, result: u8) {
+                memo.insert(state.pack.to_bits(), (result, state.minute));
+            }
+
+            // done?
+            if state.minute == limit {
+                let result = state.pack.geode;
+                insert(memo, &state, result);
+                *max_result = (*max_result).max(result);
+                return result;
+            }
+
+            // can we even beat max_result anymore?
+            {
+                // number of turns remaining.
+                let remaining = (limit - state.minute) as u32;
+
+                let max_yield =
+                      // future yield of current geode bots.
+                      remaining * state.pack.geode_robot as u32
+                      // max future yield, if we build one geode bot
+                      // on all future turns.
+                    + remaining*(remaining-1)/2;
+
+                if state.pack.geode as u32 + max_yield <= *max_result as u32 {
+                    stats::with(|s| s.pruned += 1);
+
+                    // doesn't matter what we insert,
+                    // we already have a better result.
+                    insert(memo, &state, 0);
+                    return 0;
+                }
+            }
+
+            let mut result = 0;
+
+            // building a geode bot is the best thing we can do.
+            // the proof is left as an exercise for the reader :P
+            if state.can_build_geode_robot(bp) {
+                result = result.max(solution(state.step().build_geode_robot(bp), bp, limit, memo, max_result, Can::ALL));
+            }
+            else {
+                let mut next_can = Can::ALL;
+                if state.can_build_obsidian_robot(bp) {
+                    next_can.obsidian = false;
+
+                    // can only build one bot per turn.
+                    // don't need more bots, if we're producing enough,
+                    // so we can build the most expensive bot on each turn.
+                    if can.obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
+                        result = result.max(solution(state.step().build_obsidian_robot(bp), bp, limit, memo, max_result, Can::ALL));
+                    }
+                }
+
+                if state.can_build_clay_robot(bp) {
+                    next_can.clay = false;
+
+                    if can.clay && state.pack.clay_robot < bp.max_clay_cost() {
+                        result = result.max(solution(state.step().build_clay_robot(bp), bp, limit, memo, max_result, Can::ALL));
+                    }
+                }
+
+                if state.can_build_ore_robot(bp) {
+                    next_can.ore = false;
+
+                    if can.ore && state.pack.ore_robot < bp.max_ore_cost() {
+                        result = result.max(solution(state.step().build_ore_robot(bp), bp, limit, memo, max_result, Can::ALL));
+                    }
+                }
+
+                result = result.max(solution(state.step(), bp, limit, memo, max_result, next_can));
+            }
+
+            insert(memo, &state, result);
+
+            return result;
+        }
+
+        pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
+            let mut memo = Memo::new(1 << 18, Replace::When(|old, new| new.1 < old.1));
+            let mut max_result = 0;
+            solution(State::new(), bp, limit, &mut memo, &mut max_result, Can::ALL)
+        }
+    }
+
+    // v5, but the top of the tree is searched in parallel.
+    // rayon's work stealing balances the subtrees,
+    // and the threads share the bound through an atomic `max_result`.
+    pub mod v6 {
+        use core::sync::atomic::{AtomicU8, Ordering};
+        use super::{stats, Blueprint, Can, State};
+
+        // below this minute, subtrees are too small to be worth a task.
+        const PAR_MINUTES: u8 = 10;
+
+        fn solution(state: State, bp: &Blueprint, limit: u8, max_result: &AtomicU8,
+            can: Can
+        ) {
+            stats::with(|s| s.states_visited += 1);
+
+            // done?
+            if state.minute == limit {
+                max_result.fetch_max(state.pack.geode, Ordering::Relaxed);
+                return;
+            }
+
+            // can we even beat max_result anymore?
+            {
+                let remaining = (limit - state.minute) as u32;
+                let max_yield = remaining * state.pack.geode_robot as u32 + remaining*(remaining-1)/2;
+                if state.pack.geode as u32 + max_yield <= max_result.load(Ordering::Relaxed) as u32 {
+                    stats::with(|s| s.pruned += 1);
+                    return;
+                }
+            }
+
+            // same choices as v5.
+            let mut children = [None; 4];
+            let mut wait = None;
+            if state.can_build_geode_robot(bp) {
+                children[0] = Some(state.step().build_geode_robot(bp));
+            }
+            else {
+                let mut next_can = Can::ALL;
+                if state.can_build_obsidian_robot(bp) {
+                    next_can.obsidian = false;
+                    if can.obsidian && state.pack.obsidian_robot < bp.max_obsidian_cost() {
+                        children[1] = Some(state.step().build_obsidian_robot(bp));
+                    }
+                }
+
+                if state.can_build_clay_robot(bp) {
+                    next_can.clay = false;
+                    if can.clay && state.pack.clay_robot < bp.max_clay_cost() {
+                        children[2] = Some(state.step().build_clay_robot(bp));
+                    }
+                }
+
+                if state.can_build_ore_robot(bp) {
+                    next_can.ore = false;
+                    if can.ore && state.pack.ore_robot < bp.max_ore_cost() {
+                        children[3] = Some(state.step().build_ore_robot(bp));
+                    }
+                }
+
+                wait = Some((state.step(), next_can));
+            }
+
+            if state.minute < PAR_MINUTES {
+                rayon::scope(|s| {
+                    for child in children.into_iter().flatten() {
+                        s.spawn(move |_| solution(child, bp, limit, max_result, Can::ALL));
+                    }
+                    if let Some((child, can)) = wait {
+                        s.spawn(move |_| solution(child, bp, limit, max_result, can));
+                    }
+                });
+            }
+            else {
+                for child in children.into_iter().flatten() {
+                    solution(child, bp, limit, max_result, Can::ALL);
+                }
+                if let Some((child, can)) = wait {
+                    solution(child, bp, limit, max_result, can);
+                }
+            }
+        }
+
+        pub fn solve(bp: &Blueprint, limit: u8) -> u8 {
+            let max_result = AtomicU8::new(0);
+            solution(State::new(), bp, limit, &max_result, Can::ALL);
+            max_result.into_inner()
+        }
+    }
+
+    // per blueprint: sequential v5 vs the parallel search in v6.
+    // then all blueprints at once, each solved sequentially on its own thread.
+    pub fn compare_parallel(bps: &[Blueprint], limit: u8) {
+        use rayon::prelude::*;
+
+        let time = |f: &dyn Fn() -> u8| {
+            let t0 = std::time::Instant::now();
+            let result = f();
+            (result, t0.elapsed())
+        };
+
+        let mut total_seq = std::time::Duration::ZERO;
+        let mut total_par = std::time::Duration::ZERO;
+        for bp in bps {
+            let (seq, dt_seq) = time(&|| v5::solve(bp, limit));
+            let (par, dt_par) = time(&|| v6::solve(bp, limit));
+            assert_eq!(seq, par);
+            total_seq += dt_seq;
+            total_par += dt_par;
+            println!("blueprint {:2}: {:2} geodes, v5 {:>10.2?}, v6 {:>10.2?}, {:.2}x",
+                bp.id, seq, dt_seq, dt_par, dt_seq.as_secs_f64() / dt_par.as_secs_f64());
+        }
+        println!("search: v5 {:.2?}, v6 {:.2?}, {:.2}x",
+            total_seq, total_par, total_seq.as_secs_f64() / total_par.as_secs_f64());
+
+        let t0 = std::time::Instant::now();
+        let quality: u32 = bps.par_iter().map(|bp| bp.id as u32 * v5::solve(bp, limit) as u32).sum();
+        let dt = t0.elapsed();
+        let expected: u32 = bps.iter().map(|bp| bp.id as u32 * v5::solve(bp, limit) as u32).sum();
+        assert_eq!(quality, expected);
+        println!("blueprints: v5 {:.2?}, par_iter v5 {:.2?}, {:.2}x ({} threads)",
+            total_seq, dt, total_seq.as_secs_f64() / dt.as_secs_f64(), rayon::current_num_threads());
+        println!();
+    }
+
     pub fn part_1<F: Fn(&Blueprint, u8) -> u8>(bps: &[Blueprint], f: F) {
-        super::STATS.with(|s| s.reset());
+        stats::reset();
         let t0 = std::time::Instant::now();
         let mut result = 0;
         for bp in bps {
@@ -1804,11 +2202,21 @@ mod thonk {
             result += bp.id as u32 * geodes as u32;
         }
         println!("part 1 result: {} in {:?}", result, t0.elapsed());
-        super::STATS.with(|s| s.print());
+        stats::print();
+        println!();
+    }
+
+    pub fn quality_sum(input: &str, limit: u8, solve: fn(&Blueprint, u8) -> u8) -> u32 {
+        parse(input).iter().map(|bp| bp.id as u32 * solve(bp, limit) as u32).sum()
+    }
+
+    pub fn product(input: &str, limit: u8, n: usize, solve: fn(&Blueprint, u8) -> u8) -> u32 {
+        parse(input).iter().take(n).map(|bp| solve(bp, limit) as u32).product()
     }
 
+    #[allow(dead_code)]
     pub fn part_1_ex<F: Fn(&Blueprint, u8) -> u8>(bps: &[Blueprint], f: F, n: u8) {
-        super::STATS.with(|s| s.reset());
+        stats::reset();
         let t0 = std::time::Instant::now();
         let mut result = 0;
         for bp in bps {
@@ -1816,15 +2224,18 @@ mod thonk {
             // println!("geodes: {}", geodes);
             result += bp.id as u32 * geodes as u32;
         }
+        core::hint::black_box(result);
         //println!("part 1 n: {}, result: {} in {:?}", n, result, t0.elapsed());
         println!("({}, {}), ", n, t0.elapsed().as_secs_f64());
-        super::STATS.with(|s| s.print());
+        stats::print();
+        println!();
     }
 }
 
 
 fn hash_bench_thing() {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    #[allow(dead_code)]
     struct Pack {
         ore_robot:      u32,
         clay_robot:     u32,
@@ -1873,21 +2284,23 @@ fn hash_bench_thing() {
         geode:    u8,
     }
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
-    struct Pack64 {
-        ore_robot:      u8,
-        clay_robot:     u8,
-        obsidian_robot: u8,
-        geode_robot:    u8,
-        ore:      u8,
-        clay:     u8,
-        obsidian: u8,
-        geode:    u8,
+    bit_pack! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+        struct Pack64: u64 {
+            ore_robot:      u8 = 8,
+            clay_robot:     u8 = 8,
+            obsidian_robot: u8 = 8,
+            geode_robot:    u8 = 8,
+            ore:      u8 = 8,
+            clay:     u8 = 8,
+            obsidian: u8 = 8,
+            geode:    u8 = 8,
+        }
     }
 
     impl core::hash::Hash for Pack64 {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            state.write_u64(unsafe { core::mem::transmute(*self) });
+            state.write_u64(self.to_bits());
         }
     }
 
@@ -1915,70 +2328,119 @@ fn hash_bench_thing() {
 }
 
 
-pub fn main() {
+// sequential vs parallel branch & bound, for `d19::main`.
+pub fn part_1_search(input: &str) -> u32 {
+    thonk::quality_sum(input, 24, thonk::v5::solve)
+}
+
+pub fn part_1_search_parallel(input: &str) -> u32 {
+    thonk::quality_sum(input, 24, thonk::v6::solve)
+}
+
+pub fn part_2_search(input: &str) -> u32 {
+    thonk::product(input, 32, 3, thonk::v5::solve)
+}
+
+pub fn part_2_search_parallel(input: &str) -> u32 {
+    thonk::product(input, 32, 3, thonk::v6::solve)
+}
+
+
+// the variants in groups, from slowest to fastest.
+// `cargo run --release -- yt <group>...`, `baseline` takes minutes.
+// the unpruned search for one blueprint, for `aoc tree --unpruned`.
+pub fn unpruned_tree(input: &str, id: u8, limit: u8, max_depth: usize, max_nodes: usize) -> Result<crate::util::tree::SearchTree, String> {
+    let bp = baseline::parse(input).into_iter().find(|bp| bp.id == id as u32)
+        .ok_or_else(|| format!("no blueprint {id}"))?;
+    return Ok(baseline::printer::search_tree(&bp, limit, max_depth, max_nodes));
+}
+
+pub const GROUPS: &[&str] = &["baseline", "pack", "u8", "thonk", "parallel", "hash"];
+
+pub fn main(groups: &[&str]) -> Result<(), String> {
     //let input = include_str!("d19-test.txt");
     let input = include_str!("d19-prod.txt");
     //let input = include_str!("d19-prod-2.txt");
 
-    if 0==1 {
-        hash_bench_thing();
+    for group in groups {
+        match *group {
+            "baseline" => baseline(input),
+            "pack"     => pack(input),
+            "u8"       => pack_u8(input),
+            "thonk"    => thonk(input),
+            "parallel" => parallel(input),
+            "hash"     => hash_bench_thing(),
+            _ => return Err(format!("unknown group {group:?}, expected one of {GROUPS:?}")),
+        }
     }
+    return Ok(());
+}
 
-    // baseline
-    if 1==1 {
-        use baseline::*;
+fn baseline(input: &str) {
+    use baseline::*;
 
-        let input = parse(input);
+    let input = parse(input);
 
-        //printer::tree(&input[0], 5);
+    //printer::tree(&input[0], 5);
 
-        //part_1(&input, survivor::solve);
+    //part_1(&input, survivor::solve);
 
-        //println!("baseline");
-        //part_1(case, v2::solve);
-        println!("baseline");
-        part_1(&input, v3::solve);
-    }
+    //println!("baseline");
+    //part_1(case, v2::solve);
+    println!("baseline");
+    part_1(&input, v3::solve);
+}
 
-    // pack
-    if 0==1 {
-        use pack::*;
+fn pack(input: &str) {
+    use pack::*;
 
-        let input = parse(input);
+    let input = parse(input);
 
-        println!("dp earlier hit");
-        part_1(&input, v1::solve);
-    }
+    println!("dp earlier hit");
+    part_1(&input, v1::solve);
+}
 
-    // u8
-    if 0==1 {
-        use pack_u8::*;
+fn pack_u8(input: &str) {
+    use pack_u8::*;
 
-        let input = parse(input);
+    let input = parse(input);
 
-        println!("u8");
-        part_1(&input, v1::solve);
-        println!("u8 as u64");
-        part_1(&input, v2::solve);
-    }
+    println!("u8");
+    part_1(&input, v1::solve);
+    println!("u8 as u64");
+    part_1(&input, v2::solve);
+}
 
-    // thonk
-    if 1==1 {
-        use thonk::*;
-
-        let input = parse(input);
-
-        println!("thonk max_result");
-        //part_1(&input, v1::solve);
-        println!("thonk enough bots");
-        part_1(&input, v2::solve);
-        println!("thonk don't idle");
-        part_1(&input, v3::solve);
-        println!("thonk geodes first");
-        part_1(&input, v4::solve);
-        println!("thonk no memo");
-        part_1(&input, v5::solve);
-    }
+fn thonk(input: &str) {
+    use thonk::*;
+
+    let input = parse(input);
+
+    println!("thonk max_result");
+    //part_1(&input, v1::solve);
+    println!("thonk enough bots");
+    part_1(&input, v2::solve);
+    println!("thonk don't idle");
+    part_1(&input, v3::solve);
+    println!("thonk geodes first");
+    part_1(&input, v4::solve);
+    println!("thonk memo table");
+    part_1(&input, v9::solve);
+    println!("thonk no memo");
+    part_1(&input, v5::solve);
+    println!("thonk skip to next build");
+    part_1(&input, v7::solve);
+    println!("thonk skip & relaxed bound");
+    part_1(&input, v8::solve);
 }
 
+fn parallel(input: &str) {
+    use thonk::*;
 
+    let input = parse(input);
+
+    println!("thonk parallel, 24 minutes");
+    compare_parallel(&input, 24);
+    println!("thonk parallel, 32 minutes");
+    compare_parallel(&input[..3], 32);
+}
@@ -1,3 +1,6 @@
+use crate::util::lines;
+
+
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Dir { N, S, W, E }
@@ -318,7 +321,7 @@ impl Board {
 
 fn parse(input: &str) -> Board {
     let mut board = Board::new();
-    for (y, line) in input.split("\n").enumerate() {
+    for (y, line) in lines::lines(input).enumerate() {
         for (x, ch) in line.bytes().enumerate() {
             if ch == '#' as u8 {
                 board.set_slow(x as i32, y as i32);
@@ -328,35 +331,336 @@ fn parse(input: &str) -> Board {
     board
 }
 
-fn part_1(input: &str) {
-    let t0 = std::time::Instant::now();
+// the same simulation on a sparse board of 64x64 bit tiles.
+// tiles are allocated when an elf (or a proposal) first touches them,
+// and the board never gets copied, so the elves can spread as far as they like.
+// each elf remembers its tile, and each tile its neighbours,
+// so the hash map is only used to link up new tiles.
+mod tiled {
+    use std::collections::HashMap;
+
+    use crate::util::lines;
+    use crate::util::hash::FxBuildHasher;
+
+    use super::{Dir, Rect};
+
+    const DELTAS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+    const NONE: u32 = u32::MAX;
+
+    // one bit per cell, `rows[y] >> x & 1`.
+    struct Tile {
+        elves:     [u64; 64],
+        claimed:   [u64; 64],
+        contested: [u64; 64],
+        key: (i32, i32),
+        // the tiles around this one, by `near_slot`, or `NONE`.
+        near: [u32; 9],
+    }
+
+    #[inline(always)]
+    fn near_slot(dx: i32, dy: i32) -> usize {
+        ((dy + 1)*3 + dx + 1) as usize
+    }
+
+    #[inline(always)]
+    fn locate(x: i32, y: i32) -> ((i32, i32), usize, u64) {
+        ((x >> 6, y >> 6), (y & 63) as usize, 1 << (x & 63))
+    }
+
+    struct Elf {
+        x: i32,
+        y: i32,
+        tile: u32,
+        // the target cell & its tile.
+        to: Option<(i32, i32, u32)>,
+    }
+
+    pub struct Board {
+        // boxed, so growing `tiles` only moves pointers.
+        #[allow(clippy::vec_box)]
+        tiles: Vec<Box<Tile>>,
+        lookup: HashMap<(i32, i32), u32, FxBuildHasher>,
+        aabb: Rect,
+
+        dirs: [Dir; 4],
+        dir_rot: u32,
+        elves: Vec<Elf>,
+    }
+
+    impl Board {
+        pub fn new() -> Self {
+            Board {
+                tiles: vec![],
+                lookup: HashMap::default(),
+                aabb: Rect::MAX_MIN,
+                dirs: [Dir::N, Dir::S, Dir::W, Dir::E],
+                dir_rot: 0,
+                elves: vec![],
+            }
+        }
+
+        // finds or creates the tile, and links it to its neighbours.
+        fn tile_index(&mut self, key: (i32, i32)) -> u32 {
+            if let Some(i) = self.lookup.get(&key) {
+                return *i;
+            }
+
+            let i = self.tiles.len() as u32;
+            let mut near = [NONE; 9];
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let n =
+                        if (dx, dy) == (0, 0) { i }
+                        else { self.lookup.get(&(key.0 + dx, key.1 + dy)).copied().unwrap_or(NONE) };
+                    near[near_slot(dx, dy)] = n;
+                    if n != NONE && n != i {
+                        self.tiles[n as usize].near[near_slot(-dx, -dy)] = i;
+                    }
+                }
+            }
+
+            self.tiles.push(Box::new(Tile { elves: [0; 64], claimed: [0; 64], contested: [0; 64], key, near }));
+            self.lookup.insert(key, i);
+            return i;
+        }
+
+        // the tile of `(x, y)`, which is at most one tile away from `from`.
+        #[inline]
+        fn near(&self, from: u32, x: i32, y: i32) -> u32 {
+            let (fx, fy) = self.tiles[from as usize].key;
+            return self.tiles[from as usize].near[near_slot((x >> 6) - fx, (y >> 6) - fy)];
+        }
+
+        #[inline]
+        fn get_near(&self, from: u32, x: i32, y: i32) -> bool {
+            let tile = self.near(from, x, y);
+            if tile == NONE {
+                return false;
+            }
+            let (_, row, bit) = locate(x, y);
+            return self.tiles[tile as usize].elves[row] & bit != 0;
+        }
+
+        fn get(&self, x: i32, y: i32) -> bool {
+            let (key, row, bit) = locate(x, y);
+            self.lookup.get(&key).map(|i| self.tiles[*i as usize].elves[row] & bit != 0).unwrap_or(false)
+        }
+
+        pub fn set(&mut self, x: i32, y: i32) {
+            let (key, row, bit) = locate(x, y);
+            let tile = self.tile_index(key);
+            let t = &mut self.tiles[tile as usize];
+            if t.elves[row] & bit == 0 {
+                t.elves[row] |= bit;
+                self.elves.push(Elf { x, y, tile, to: None });
+                self.aabb = self.aabb.include(x, y);
+            }
+        }
+
+        // the rows above, at, and below `(x, y)` in `tile`, three bits each.
+        // bit 0 is `x-1`, bit 2 is `x+1`.
+        #[inline]
+        fn around(&self, tile: u32, x: i32, y: i32) -> [u64; 3] {
+            let row = (y & 63) as usize;
+            let lx = x & 63;
+            if lx > 0 && lx < 63 && row > 0 && row < 63 {
+                let tile = &self.tiles[tile as usize];
+                let shift = lx - 1;
+                return [
+                    tile.elves[row - 1] >> shift & 7,
+                    tile.elves[row]     >> shift & 7,
+                    tile.elves[row + 1] >> shift & 7,
+                ];
+            }
+
+            // on a tile border.
+            let row3 = |y| {
+                  (self.get_near(tile, x - 1, y) as u64)
+                | (self.get_near(tile, x,     y) as u64) << 1
+                | (self.get_near(tile, x + 1, y) as u64) << 2
+            };
+            return [row3(y - 1), row3(y), row3(y + 1)];
+        }
+
+        #[allow(dead_code)]
+        pub fn print(&self) {
+            for y in self.aabb.y0..self.aabb.y1 {
+                for x in self.aabb.x0..self.aabb.x1 {
+                    print!("{}", if self.get(x, y) { "#" } else { "." });
+                }
+                println!();
+            }
+            println!();
+        }
+
+        pub fn step(&mut self) -> bool {
+            let dirs = self.dirs;
+            let dir_rot = self.dir_rot;
+
+            // proposals.
+            // a cell claimed twice is contested, and nobody moves there.
+            for e in 0..self.elves.len() {
+                let Elf { x, y, tile, .. } = self.elves[e];
 
+                let [n, mid, s] = self.around(tile, x, y);
+                if n | (mid & !2) | s == 0 {
+                    continue;
+                }
+
+                // one byte per direction, like `super::Board::step`.
+                let side = n | mid | s;
+                let cans = ((n == 0) as u32) | ((s == 0) as u32) << 8 | ((side & 1 == 0) as u32) << 16 | ((side & 4 == 0) as u32) << 24;
+                let cans = cans.rotate_right(dir_rot * 8);
+                if cans == 0 {
+                    continue;
+                }
+                let dir = dirs[(cans.trailing_zeros() / 8) as usize];
+
+                let (dx, dy) = DELTAS[dir as usize];
+                let (tx, ty) = (x + dx, y + dy);
+                let (key, row, bit) = locate(tx, ty);
+                let mut to_tile = tile;
+                if (tx ^ x) >> 6 != 0 || (ty ^ y) >> 6 != 0 {
+                    to_tile = self.near(tile, tx, ty);
+                    if to_tile == NONE {
+                        to_tile = self.tile_index(key);
+                    }
+                }
+                self.elves[e].to = Some((tx, ty, to_tile));
+
+                let t = &mut self.tiles[to_tile as usize];
+                if t.claimed[row] & bit != 0 {
+                    t.contested[row] |= bit;
+                }
+                t.claimed[row] |= bit;
+            }
+
+            // moves, and clear the claims.
+            // at most two elves claim a cell, from opposite sides.
+            // so the first one of a contested pair clears `claimed`, the second `contested`.
+            self.aabb = Rect::MAX_MIN;
+            let mut num_moves = 0;
+            for e in 0..self.elves.len() {
+                if let Some((tx, ty, to_tile)) = self.elves[e].to.take() {
+                    let (_, row, bit) = locate(tx, ty);
+                    let t = &mut self.tiles[to_tile as usize];
+                    if t.contested[row] & bit != 0 {
+                        if t.claimed[row] & bit != 0 { t.claimed[row]   &= !bit }
+                        else                         { t.contested[row] &= !bit }
+                    }
+                    else {
+                        num_moves += 1;
+                        t.elves[row]   |= bit;
+                        t.claimed[row] &= !bit;
+
+                        let elf = &mut self.elves[e];
+                        let (x, y, from_tile) = (elf.x, elf.y, elf.tile);
+                        elf.x = tx;
+                        elf.y = ty;
+                        elf.tile = to_tile;
+
+                        let (_, row, bit) = locate(x, y);
+                        self.tiles[from_tile as usize].elves[row] &= !bit;
+                    }
+                }
+                let elf = &self.elves[e];
+                self.aabb = self.aabb.include(elf.x, elf.y);
+            }
+
+            self.dirs.rotate_left(1);
+            self.dir_rot += 1;
+            self.dir_rot %= 4;
+
+            num_moves != 0
+        }
+
+        pub fn empty_tiles(&self) -> u32 {
+            let w = self.aabb.w() as u32;
+            let h = self.aabb.h() as u32;
+            w*h - self.elves.len() as u32
+        }
+
+        #[allow(dead_code)]
+        pub fn num_tiles(&self) -> usize {
+            self.tiles.len()
+        }
+    }
+
+    pub fn parse(input: &str) -> Board {
+        let mut board = Board::new();
+        for (y, line) in lines::lines(input).enumerate() {
+            for (x, ch) in line.bytes().enumerate() {
+                if ch == b'#' {
+                    board.set(x as i32, y as i32);
+                }
+            }
+        }
+        board
+    }
+}
+
+fn part_1(input: &str) -> u32 {
     let mut board = parse(input);
     for _ in 0..10 {
         board.step();
     }
-    println!("part 1: {} in {:?}", board.empty_tiles(), t0.elapsed());
+    return board.empty_tiles();
 }
 
-fn part_2(input: &str) {
-    //for _ in 0..100 {
-    let t0 = std::time::Instant::now();
-
+fn part_2(input: &str) -> u32 {
     let mut board = parse(input);
     let mut i = 1;
     while board.step() {
         i += 1;
     }
-    println!("part 2: {} in {:?}", i, t0.elapsed());
-    //}
+    return i;
 }
 
-pub fn main() {
-    part_1(include_str!("d23-test.txt"));
-    part_2(include_str!("d23-test.txt"));
-    part_1(include_str!("d23-prod.txt"));
-    part_2(include_str!("d23-prod.txt"));
-    part_1(include_str!("d23-prod-2.txt"));
-    part_2(include_str!("d23-prod-2.txt"));
+fn part_1_tiled(input: &str) -> u32 {
+    let mut board = tiled::parse(input);
+    for _ in 0..10 {
+        board.step();
+    }
+    return board.empty_tiles();
 }
 
+fn part_2_tiled(input: &str) -> u32 {
+    let mut board = tiled::parse(input);
+    let mut i = 1;
+    while board.step() {
+        i += 1;
+    }
+    return i;
+}
+
+
+fn run(name: &str, f: impl FnOnce(&str) -> u32, input: &str) {
+    let t0 = std::time::Instant::now();
+    let result = f(input);
+    let dt = t0.elapsed();
+    println!("{name}: {result} in {dt:?}, {:.2} MiB/s",
+        input.len() as f64 / dt.as_secs_f64() / 1024.0 / 1024.0);
+}
+
+pub fn main() {
+    println!("-- 2022 day 23 --");
+
+    run("part_1", part_1, include_str!("d23-test.txt"));
+    run("part_1", part_1, include_str!("d23-prod.txt"));
+    run("part_1", part_1, include_str!("d23-prod-2.txt"));
+
+    run("part_2", part_2, include_str!("d23-test.txt"));
+    run("part_2", part_2, include_str!("d23-prod.txt"));
+    run("part_2", part_2, include_str!("d23-prod-2.txt"));
+
+    run("part_1_tiled", part_1_tiled, include_str!("d23-test.txt"));
+    run("part_1_tiled", part_1_tiled, include_str!("d23-prod.txt"));
+    run("part_1_tiled", part_1_tiled, include_str!("d23-prod-2.txt"));
+
+    run("part_2_tiled", part_2_tiled, include_str!("d23-test.txt"));
+    run("part_2_tiled", part_2_tiled, include_str!("d23-prod.txt"));
+    run("part_2_tiled", part_2_tiled, include_str!("d23-prod-2.txt"));
+
+    println!();
+}
@@ -1,5 +1,5 @@
-//pub mod d19;
-//pub mod d23;
-
-//pub mod d19_yt;
+pub mod d19;
+pub mod d23;
 
+// the solver variants from the video, run with `aoc yt <group>`.
+pub mod d19_yt;
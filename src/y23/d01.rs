@@ -1,12 +1,15 @@
-use std::collections::{HashSet, HashMap};
+use std::collections::VecDeque;
 
 
 use rayon::prelude::*;
 
+use crate::util::chunks::{self, Split};
+use crate::util::lines;
+
 
 fn part_1(input: &str) -> i32 {
     let mut result = 0;
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let mut first = None;
         let mut last = None;
         for b in line.bytes() {
@@ -31,7 +34,7 @@ fn part_1(input: &str) -> i32 {
 
 fn part_2(input: &str) -> i32 {
     let mut result = 0;
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let mut first = None;
         let mut last = None;
 
@@ -94,99 +97,115 @@ fn part_2(input: &str) -> i32 {
 }
 
 
-fn part_2_make_fsm_ex(rev: bool, eol: bool) -> Vec<(u8, u8)> {
-    let words =
-        if !rev { [ "one", "two", "three", "four", "five", "six", "seven", "eight", "nine" ] }
-        else    { [ "eno", "owt", "eerht", "ruof", "evif", "xis", "neves", "thgie", "enin" ] };
-
-    let mut vocab = HashSet::new();
-    for word in words {
-        for c in word.as_bytes() {
-            vocab.insert(*c);
-        }
-    }
+const DIGIT_WORDS: [(&str, u8); 18] = [
+    ("1", 1), ("2", 2), ("3", 3), ("4", 4), ("5", 5), ("6", 6), ("7", 7), ("8", 8), ("9", 9),
+    ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5),
+    ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
 
-    let mut word_map = HashMap::new();
-    for (i, word) in words.iter().enumerate() {
-        word_map.insert(word.as_bytes(), (i + 1) as u8);
-    }
+struct FsmTable {
+    // `state*256 + byte -> (next state, value + 1)`.
+    // the value is 0 if no word ends on this byte, 255 for end of line.
+    table: Vec<(u8, u8)>,
+    num_states: usize,
+}
 
-    let mut states = vec![""];
-    let mut state_map = HashMap::new();
-    state_map.insert("", 0);
+impl FsmTable {
+    fn size_bytes(&self) -> usize {
+        self.table.len() * core::mem::size_of::<(u8, u8)>()
+    }
+}
 
-    let mut n = 0;
-    loop {
-        n += 1;
-
-        let mut any = false;
-        for word in words {
-            if n < word.len() {
-                any = true;
-
-                let buf = &word[..n];
-                if !state_map.contains_key(buf) {
-                    state_map.insert(buf, states.len() as u8);
-                    states.push(buf);
-                }
+// aho-corasick automaton for `words`.
+// when several words end on the same byte, the longest one wins.
+fn make_fsm(words: &[(&str, u8)], rev: bool, eol: bool) -> FsmTable {
+    const NONE: u32 = u32::MAX;
+
+    // trie.
+    let mut children = vec![[NONE; 256]];
+    let mut output = vec![0u8];
+    for (word, value) in words.iter().copied() {
+        assert!(value < 254);
+
+        let mut bytes = word.as_bytes().to_vec();
+        if rev { bytes.reverse() }
+
+        let mut at = 0;
+        for c in bytes {
+            if children[at][c as usize] == NONE {
+                children[at][c as usize] = children.len() as u32;
+                children.push([NONE; 256]);
+                output.push(0);
             }
+            at = children[at][c as usize] as usize;
         }
-        if !any { break }
+        assert!(at != 0 && output[at] == 0, "empty or duplicate word {word:?}");
+        output[at] = value + 1;
     }
 
-    let mut table = vec![(0u8, 0u8); states.len()*256];
-    for (i, state) in states.iter().enumerate() {
-        let map = &mut table[i*256 .. (i+1)*256];
-
-        map[b'1' as usize] = (0, 1);
-        map[b'2' as usize] = (0, 2);
-        map[b'3' as usize] = (0, 3);
-        map[b'4' as usize] = (0, 4);
-        map[b'5' as usize] = (0, 5);
-        map[b'6' as usize] = (0, 6);
-        map[b'7' as usize] = (0, 7);
-        map[b'8' as usize] = (0, 8);
-        map[b'9' as usize] = (0, 9);
-
-        if eol {
-            map[b'\n' as usize] = (0, 255);
+    // failure links & full transitions, in bfs order,
+    // so `fail[s]` is always done before `s`.
+    let mut fail = vec![0usize; children.len()];
+    let mut next = vec![[0u32; 256]; children.len()];
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+    while let Some(s) = queue.pop_front() {
+        if output[s] == 0 {
+            output[s] = output[fail[s]];
         }
 
-        let mut buf = Vec::from(state.as_bytes());
-
-        for c in vocab.iter().copied() {
-            buf.push(c);
-
-            let a = word_map.get(&*buf).copied().unwrap_or(0);
-
-            //println!("{:?}: {a}", core::str::from_utf8(&buf).unwrap());
-
-            let mut s = 0;
-            for (i, state) in states.iter().enumerate() {
-                if buf.ends_with(state.as_bytes()) {
-                    //println!("match {:?}", state);
-                    if s != 0 {
-                        let old_match = states[s];
-                        if state.ends_with(old_match) {
-                            s = i;
-                        }
-                        else { assert!(old_match.ends_with(state)) }
-                    }
-                    else {
-                        s = i;
-                    }
-                }
+        for c in 0..256 {
+            let child = children[s][c];
+            if child != NONE {
+                fail[child as usize] = if s == 0 { 0 } else { next[fail[s]][c] as usize };
+                next[s][c] = child;
+                queue.push_back(child as usize);
             }
+            else {
+                next[s][c] = if s == 0 { 0 } else { next[fail[s]][c] };
+            }
+        }
+    }
 
-            map[c as usize] = (s as u8, a);
+    // a word's leaf behaves exactly like its failure state,
+    // cause the output is on the transition into it.
+    // so we only keep the root & states with children.
+    let keep = |s: usize| s == 0 || children[s].iter().any(|c| *c != NONE);
+    let rep = |mut s: usize| {
+        while !keep(s) { s = fail[s] }
+        s
+    };
+
+    let mut ids = vec![usize::MAX; children.len()];
+    let mut num_states = 0;
+    for s in 0..children.len() {
+        if keep(s) {
+            ids[s] = num_states;
+            num_states += 1;
+        }
+    }
+    assert!(num_states <= 256);
 
-            buf.pop();
+    let mut table = vec![(0u8, 0u8); num_states*256];
+    for s in 0..children.len() {
+        if !keep(s) { continue }
+
+        let map = &mut table[ids[s]*256 .. (ids[s]+1)*256];
+        for c in 0..256 {
+            let to = next[s][c] as usize;
+            map[c] = (ids[rep(to)] as u8, output[to]);
         }
 
-        //println!("{map:?}");
+        if eol {
+            map[b'\n' as usize] = (0, 255);
+        }
     }
 
-    return table;
+    return FsmTable { table, num_states };
+}
+
+fn part_2_make_fsm_ex(rev: bool, eol: bool) -> Vec<(u8, u8)> {
+    make_fsm(&DIGIT_WORDS, rev, eol).table
 }
 
 fn part_2_make_fsm() -> Vec<(u8, u8)> {
@@ -194,6 +213,9 @@ fn part_2_make_fsm() -> Vec<(u8, u8)> {
 }
 
 fn part_2_fsm(input: &str, table: &[(u8, u8)]) -> i32 {
+    // one row of 256 per state, so the byte index is always in bounds.
+    let (table, _) = table.as_chunks::<256>();
+
     let mut result = 0;
     let mut n = 0;
     let mut vs = [0, 0];
@@ -201,9 +223,7 @@ fn part_2_fsm(input: &str, table: &[(u8, u8)]) -> i32 {
     let mut state = 0;
 
     for i in input.bytes() {
-        let (s, a) = unsafe {
-            *table.get_unchecked(state as usize * 256 + i as usize)
-        };
+        let (s, a) = table[state as usize][i as usize];
         state = s;
 
         //println!("{state} {:?} -> {s} {a}", i as char);
@@ -219,7 +239,7 @@ fn part_2_fsm(input: &str, table: &[(u8, u8)]) -> i32 {
         }
         else if a != 0 {
             //println!("a: {a}");
-            vs[n.min(1)] = a as i32;
+            vs[n.min(1)] = (a - 1) as i32;
             n += 1;
         }
     }
@@ -229,33 +249,7 @@ fn part_2_fsm(input: &str, table: &[(u8, u8)]) -> i32 {
 }
 
 fn part_2_fsm_threaded(input: &str, table: &[(u8, u8)]) -> i32 {
-    let n = if input.len() < 128 { 1 } else { 12 };
-
-    (0..n)
-    .into_par_iter()
-    .map(move |i| {
-        let chunk_size = input.len() / n;
-
-        let mut begin = chunk_size * i;
-        let mut end = chunk_size * (i+1);
-
-        while begin > 0 && input.as_bytes()[begin - 1] != b'\n' {
-            begin -= 1;
-        }
-
-        if i < n-1 {
-            while end > begin && input.as_bytes()[end - 1] != b'\n' {
-                end -= 1;
-            }
-        }
-        else {
-            end = input.len();
-        }
-
-        //println!("{begin}..{end}");
-
-        part_2_fsm(&input[begin..end], table)
-    }).sum()
+    chunks::par_sum(input, Split::Lines, |chunk| part_2_fsm(chunk, table))
 }
 
 
@@ -265,38 +259,38 @@ fn part_2_make_fsm2() -> (Vec<(u8, u8)>, Vec<(u8, u8)>) {
 }
 
 fn part_2_fsm2(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
+    let (fwd, _) = fwd.as_chunks::<256>();
+    let (bwd, _) = bwd.as_chunks::<256>();
+
     let mut result = 0;
 
-    for line in input.as_bytes().split(|at| *at == b'\n') {
+    for line in lines::byte_lines(input.as_bytes()) {
         let mut vs = [0, 0];
         let mut state = 0;
 
         for i in line.iter().copied() {
-            let (s, a) = unsafe {
-                *fwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = fwd[state as usize][i as usize];
             state = s;
 
             //println!("{state} {:?} -> {s} {a}", i as char);
 
             if a != 0 {
                 //println!("a: {a}");
-                vs[0] = a as i32;
+                vs[0] = (a - 1) as i32;
                 break;
             }
         }
 
+        state = 0;
         for i in line.iter().rev().copied() {
-            let (s, a) = unsafe {
-                *bwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = bwd[state as usize][i as usize];
             state = s;
 
             //println!("{state} {:?} -> {s} {a}", i as char);
 
             if a != 0 {
                 //println!("a: {a}");
-                vs[1] = a as i32;
+                vs[1] = (a - 1) as i32;
                 break;
             }
         }
@@ -308,96 +302,43 @@ fn part_2_fsm2(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
 }
 
 fn part_2_fsm2_threaded(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
-    let n = if input.len() < 128 { 1 } else { 12 };
-
-    (0..n)
-    .into_par_iter()
-    .map(move |i| {
-        let chunk_size = input.len() / n;
-
-        let mut begin = chunk_size * i;
-        let mut end = chunk_size * (i+1);
-
-        while begin > 0 && input.as_bytes()[begin - 1] != b'\n' {
-            begin -= 1;
-        }
-
-        if i < n-1 {
-            while end > begin && input.as_bytes()[end - 1] != b'\n' {
-                end -= 1;
-            }
-        }
-        else {
-            end = input.len();
-        }
-
-        //println!("{begin}..{end}");
-
-        part_2_fsm2(&input[begin..end], fwd, bwd)
-    }).sum()
+    chunks::par_sum(input, Split::Lines, |chunk| part_2_fsm2(chunk, fwd, bwd))
 }
 
 
 fn part_2_fsm2_vect(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
     let mut result = 0;
 
-    let mut input = input.as_bytes();
-    while input.len() > 0 {
-        let line = {
-            let mut len = 0;
-
-            while len + 8 < input.len() {
-                let bytes = unsafe { input.as_ptr().add(len).cast::<u64>().read_unaligned() };
-                let bytes = bytes ^ 0x0a0a0a0a0a0a0a0a;
-                let zero_or_high = bytes.wrapping_sub(0x0101010101010101);
-                let not_high = !bytes & 0x8080808080808080;
-                let mask = zero_or_high & not_high;
-
-                if mask != 0 {
-                    len += (mask.trailing_zeros() / 8) as usize;
-                    break;
-                }
-                len += 8;
-            }
-
-            while len < input.len() && input[len] != b'\n' {
-                len += 1;
-            }
-
-            let line = &input[..len];
-            input = &input[len+1..];
-            line
-        };
+    // one row of 256 per state, so only the state index needs a bounds check.
+    let (fwd, bwd) = (fwd.as_chunks::<256>().0, bwd.as_chunks::<256>().0);
 
+    for line in lines::byte_lines(input.as_bytes()) {
         let mut vs = [0, 0];
         let mut state = 0;
 
         for i in line.iter().copied() {
-            let (s, a) = unsafe {
-                *fwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = fwd[state as usize][i as usize];
             state = s;
 
             //println!("{state} {:?} -> {s} {a}", i as char);
 
             if a != 0 {
                 //println!("a: {a}");
-                vs[0] = a as i32;
+                vs[0] = (a - 1) as i32;
                 break;
             }
         }
 
+        state = 0;
         for i in line.iter().rev().copied() {
-            let (s, a) = unsafe {
-                *bwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = bwd[state as usize][i as usize];
             state = s;
 
             //println!("{state} {:?} -> {s} {a}", i as char);
 
             if a != 0 {
                 //println!("a: {a}");
-                vs[1] = a as i32;
+                vs[1] = (a - 1) as i32;
                 break;
             }
         }
@@ -409,94 +350,37 @@ fn part_2_fsm2_vect(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
 }
 
 fn part_2_fsm2_vect_threaded(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
-    let n = if input.len() < 128 { 1 } else { 12 };
-
-    (0..n)
-    .into_par_iter()
-    .map(move |i| {
-        let chunk_size = input.len() / n;
-
-        let mut begin = chunk_size * i;
-        let mut end = chunk_size * (i+1);
-
-        while begin > 0 && input.as_bytes()[begin - 1] != b'\n' {
-            begin -= 1;
-        }
-
-        if i < n-1 {
-            while end > begin && input.as_bytes()[end - 1] != b'\n' {
-                end -= 1;
-            }
-        }
-        else {
-            end = input.len();
-        }
-
-        //println!("{begin}..{end}");
-
-        part_2_fsm2_vect(&input[begin..end], fwd, bwd)
-    }).sum()
+    chunks::par_sum(input, Split::Lines, |chunk| part_2_fsm2_vect(chunk, fwd, bwd))
 }
 
 
 fn part_2_fsm3(input: &str, fwd: &[(u8, u8)], bwd: &[(u8, u8)]) -> i32 {
-    let mut input = input.as_bytes();
-
-    let iter = core::iter::from_fn(|| {
-        if input.len() == 0 {
-            return None;
-        }
-
-        let mut len = 0;
-
-        while len + 8 < input.len() {
-            let bytes = unsafe { input.as_ptr().add(len).cast::<u64>().read_unaligned() };
-            let bytes = bytes ^ 0x0a0a0a0a0a0a0a0a;
-            let zero_or_high = bytes.wrapping_sub(0x0101010101010101);
-            let not_high = !bytes & 0x8080808080808080;
-            let mask = zero_or_high & not_high;
-
-            if mask != 0 {
-                len += (mask.trailing_zeros() / 8) as usize;
-                break;
-            }
-            len += 8;
-        }
-
-        while len < input.len() && input[len] != b'\n' {
-            len += 1;
-        }
-
-        let line = &input[..len];
-        input = &input[len+1..];
-        return Some(line);
-    });
+    // one row of 256 per state, so only the state index needs a bounds check.
+    let (fwd, bwd) = (fwd.as_chunks::<256>().0, bwd.as_chunks::<256>().0);
 
+    let iter = lines::byte_lines(input.as_bytes());
 
     iter.par_bridge().map(|line| {
         let mut vs = [0, 0];
         let mut state = 0;
 
         for i in line.iter().copied() {
-            let (s, a) = unsafe {
-                *fwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = fwd[state as usize][i as usize];
             state = s;
 
             if a != 0 {
-                vs[0] = a as i32;
+                vs[0] = (a - 1) as i32;
                 break;
             }
         }
 
+        state = 0;
         for i in line.iter().rev().copied() {
-            let (s, a) = unsafe {
-                *bwd.get_unchecked(state as usize * 256 + i as usize)
-            };
+            let (s, a) = bwd[state as usize][i as usize];
             state = s;
 
             if a != 0 {
-                vs[1] = a as i32;
+                vs[1] = (a - 1) as i32;
                 break;
             }
         }
@@ -559,6 +443,21 @@ pub fn main() {
     run("part_2_fsm3", |i| part_2_fsm3(i, &fsm2a, &fsm2b), include_str!("d01-test-2.txt"));
     run("part_2_fsm3", |i| part_2_fsm3(i, &fsm2a, &fsm2b), include_str!("d01-prod.txt"));
 
+    for (rev, eol) in [(false, true), (false, false), (true, false)] {
+        let fsm = make_fsm(&DIGIT_WORDS, rev, eol);
+        println!("fsm rev: {rev}, eol: {eol}: {} states, {} bytes", fsm.num_states, fsm.size_bytes());
+    }
+
+    let german = [
+        ("0", 0), ("1", 1), ("2", 2), ("3", 3), ("4", 4), ("5", 5), ("6", 6), ("7", 7), ("8", 8), ("9", 9),
+        ("null", 0), ("eins", 1), ("zwei", 2), ("drei", 3), ("vier", 4),
+        ("fuenf", 5), ("sechs", 6), ("sieben", 7), ("acht", 8), ("neun", 9),
+    ];
+    let (de_fwd, de_bwd) = (make_fsm(&german, false, false), make_fsm(&german, true, false));
+    println!("german: {} + {} states", de_fwd.num_states, de_bwd.num_states);
+
+    run("part_2_fsm2 german", |i| part_2_fsm2(i, &de_fwd.table, &de_bwd.table), "zweiundvierzig\nachtnullsieben\nnullneun\n");
+
     println!();
 
 
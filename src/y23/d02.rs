@@ -1,10 +1,13 @@
+use crate::util::chunks::{self, Split};
+use crate::util::lines;
+
 
 fn part_1(input: &str) -> i32 {
     let red_cubes = 12;
     let green_cubes = 13;
     let blue_cubes = 14;
 
-    input.lines()
+    lines::lines(input)
     .map(|line| {
         let (game, sets) = line.split_once(": ").unwrap();
         let ok = sets.split("; ").all(|set| {
@@ -31,7 +34,7 @@ fn part_1(input: &str) -> i32 {
 
 
 fn part_2(input: &str) -> i32 {
-    input.lines()
+    lines::lines(input)
     .map(|line| {
         let (_, sets) = line.split_once(": ").unwrap();
 
@@ -57,7 +60,104 @@ fn part_2(input: &str) -> i32 {
 }
 
 
-fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
+// `(colour, count)` pairs.
+#[derive(Clone, Debug, Default)]
+struct Draw<'a> {
+    cubes: Vec<(&'a str, u32)>,
+}
+
+impl<'a> Draw<'a> {
+    fn count(&self, colour: &str) -> u32 {
+        self.cubes.iter().find(|(c, _)| *c == colour).map(|(_, n)| *n).unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Game<'a> {
+    id: u32,
+    draws: Vec<Draw<'a>>,
+}
+
+impl<'a> Game<'a> {
+    fn parse(line: &'a str) -> Self {
+        let (game, draws) = line.split_once(": ").unwrap();
+        let id = game.strip_prefix("Game ").unwrap().parse().unwrap();
+
+        let draws = Vec::from_iter(draws.split("; ").map(|draw| Draw {
+            cubes: Vec::from_iter(draw.split(", ").map(|sample| {
+                let (count, colour) = sample.split_once(" ").unwrap();
+                (colour, count.parse().unwrap())
+            })),
+        }));
+
+        return Self { id, draws };
+    }
+
+    // colours missing from the bag have zero cubes.
+    fn is_feasible(&self, bag: &[(&str, u32)]) -> bool {
+        self.draws.iter().all(|draw| {
+            draw.cubes.iter().all(|(colour, count)| {
+                let have = bag.iter().find(|(c, _)| c == colour).map(|(_, n)| *n).unwrap_or(0);
+                *count <= have
+            })
+        })
+    }
+
+    // the fewest cubes of each colour that make the game possible.
+    fn min_bag(&self) -> Vec<(&'a str, u32)> {
+        let mut result: Vec<(&str, u32)> = vec![];
+        for draw in &self.draws {
+            for (colour, count) in draw.cubes.iter().copied() {
+                match result.iter_mut().find(|(c, _)| *c == colour) {
+                    Some((_, n)) => *n = (*n).max(count),
+                    None => result.push((colour, count)),
+                }
+            }
+        }
+        return result;
+    }
+
+    // product of the min bag over `colours`.
+    // a colour that's never drawn needs zero cubes, like in `part_2`.
+    fn power(&self, colours: &[&str]) -> u64 {
+        let bag = self.min_bag();
+        colours.iter().map(|colour| {
+            bag.iter().find(|(c, _)| c == colour).map(|(_, n)| *n as u64).unwrap_or(0)
+        }).product()
+    }
+}
+
+fn parse_games(input: &str) -> Vec<Game<'_>> {
+    Vec::from_iter(lines::lines(input).map(Game::parse))
+}
+
+fn feasible_id_sum(games: &[Game], bag: &[(&str, u32)]) -> u64 {
+    games.iter().filter(|g| g.is_feasible(bag)).map(|g| g.id as u64).sum()
+}
+
+const BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+fn part_1_games(input: &str) -> u64 {
+    feasible_id_sum(&parse_games(input), &BAG)
+}
+
+fn part_2_games(input: &str) -> u64 {
+    let colours = BAG.map(|(colour, _)| colour);
+    parse_games(input).iter().map(|g| g.power(&colours)).sum()
+}
+
+
+fn part_1_threaded(input: &str) -> i32 {
+    chunks::par_sum(input, Split::Lines, part_1)
+}
+
+
+fn part_2_threaded(input: &str) -> i32 {
+    chunks::par_sum(input, Split::Lines, part_2)
+}
+
+
+fn run<T: core::fmt::Display>(name: &str, f: impl FnOnce(&str) -> T, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
     let dt = t0.elapsed();
@@ -66,10 +166,27 @@ fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
 }
 
 pub fn main() {
+    println!("-- day 02 --");
+
     run("part_1", part_1, include_str!("d02-test.txt"));
     run("part_1", part_1, include_str!("d02-prod.txt"));
 
     run("part_2", part_2, include_str!("d02-test.txt"));
     run("part_2", part_2, include_str!("d02-prod.txt"));
-}
 
+    run("part_1_games", part_1_games, include_str!("d02-test.txt"));
+    run("part_1_games", part_1_games, include_str!("d02-prod.txt"));
+
+    run("part_2_games", part_2_games, include_str!("d02-test.txt"));
+    run("part_2_games", part_2_games, include_str!("d02-prod.txt"));
+
+    run("part_1_threaded", part_1_threaded, include_str!("d02-prod.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d02-prod.txt"));
+
+    let games = parse_games(include_str!("d02-test.txt"));
+    println!("feasible with 20 red, 2 green, 6 blue: {}", feasible_id_sum(&games, &[("red", 20), ("green", 2), ("blue", 6)]));
+    println!("game 3 min bag: {:?}, first draw blue: {}", games[2].min_bag(), games[2].draws[0].count("blue"));
+    println!("power without green: {}", Game::parse("Game 1: 3 red, 4 blue; 1 red").power(&BAG.map(|(c, _)| c)));
+
+    println!();
+}
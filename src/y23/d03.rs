@@ -148,6 +148,128 @@ fn part_2(input: &str) -> i32 {
 }
 
 
+#[derive(Clone, Copy, Debug)]
+struct Number {
+    value: u32,
+    y: usize,
+    x0: usize,
+    x1: usize, // exclusive
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Symbol {
+    ch: u8,
+    x: usize,
+    y: usize,
+}
+
+struct PartIndex {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+
+    // adjacency, by index into `numbers` & `symbols`.
+    symbol_numbers: Vec<Vec<usize>>,
+    number_symbols: Vec<Vec<usize>>,
+}
+
+impl PartIndex {
+    fn new(input: &str) -> Self {
+        let w = input.bytes().position(|b| b == b'\n').unwrap();
+        let s = w+1;
+        let h = input.len().div_ceil(s);
+
+        let input = input.as_bytes();
+
+        // number index of each cell, `usize::MAX` if none.
+        let mut owner = vec![usize::MAX; s*h];
+
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        for y in 0..h {
+            let line = &input[y*s .. (y*s+w).min(input.len())];
+
+            let mut x = 0;
+            while x < line.len() {
+                match line[x] {
+                    b'.' => x += 1,
+
+                    b'0'..=b'9' => {
+                        let x0 = x;
+                        let mut value = 0;
+                        while x < line.len() && line[x].is_ascii_digit() {
+                            value = 10*value + (line[x] - b'0') as u32;
+                            owner[y*s + x] = numbers.len();
+                            x += 1;
+                        }
+                        numbers.push(Number { value, y, x0, x1: x });
+                    }
+
+                    ch => {
+                        symbols.push(Symbol { ch, x, y });
+                        x += 1;
+                    }
+                }
+            }
+        }
+
+        let mut symbol_numbers = vec![vec![]; symbols.len()];
+        let mut number_symbols = vec![vec![]; numbers.len()];
+        for (i, sym) in symbols.iter().enumerate() {
+            let ns = &mut symbol_numbers[i];
+
+            for y in sym.y.saturating_sub(1) ..= (sym.y+1).min(h-1) {
+                for x in sym.x.saturating_sub(1) ..= (sym.x+1).min(w-1) {
+                    let n = owner[y*s + x];
+                    if n != usize::MAX && !ns.contains(&n) {
+                        ns.push(n);
+                        number_symbols[n].push(i);
+                    }
+                }
+            }
+        }
+
+        return Self { numbers, symbols, symbol_numbers, number_symbols };
+    }
+
+    // numbers next to any symbol `ch`.
+    fn numbers_next_to(&self, ch: u8) -> impl Iterator<Item = &Number> + '_ {
+        self.number_symbols.iter().enumerate()
+        .filter(move |(_, syms)| syms.iter().any(|s| self.symbols[*s].ch == ch))
+        .map(|(n, _)| &self.numbers[n])
+    }
+
+    // symbols `ch` with exactly `n` adjacent numbers.
+    fn gears(&self, ch: u8, n: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.symbols.len())
+        .filter(move |i| self.symbols[*i].ch == ch && self.symbol_numbers[*i].len() == n)
+    }
+
+    // numbers adjacent to no symbol.
+    fn loose_numbers(&self) -> impl Iterator<Item = &Number> + '_ {
+        self.number_symbols.iter().enumerate()
+        .filter(|(_, syms)| syms.is_empty())
+        .map(|(n, _)| &self.numbers[n])
+    }
+}
+
+
+fn part_1_index(input: &str) -> i32 {
+    let index = PartIndex::new(input);
+    index.numbers.iter().zip(&index.number_symbols)
+    .filter(|(_, syms)| !syms.is_empty())
+    .map(|(n, _)| n.value as i32)
+    .sum()
+}
+
+
+fn part_2_index(input: &str) -> i32 {
+    let index = PartIndex::new(input);
+    index.gears(b'*', 2)
+    .map(|g| index.symbol_numbers[g].iter().map(|n| index.numbers[*n].value as i32).product::<i32>())
+    .sum()
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -157,10 +279,24 @@ fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
 }
 
 pub fn main() {
+    println!("-- day 03 --");
+
     run("part_1", part_1, include_str!("d03-test.txt"));
     run("part_1", part_1, include_str!("d03-prod.txt"));
 
     run("part_2", part_2, include_str!("d03-test.txt"));
     run("part_2", part_2, include_str!("d03-prod.txt"));
-}
 
+    run("part_1_index", part_1_index, include_str!("d03-test.txt"));
+    run("part_1_index", part_1_index, include_str!("d03-prod.txt"));
+
+    run("part_2_index", part_2_index, include_str!("d03-test.txt"));
+    run("part_2_index", part_2_index, include_str!("d03-prod.txt"));
+
+    let index = PartIndex::new(include_str!("d03-test.txt"));
+    println!("loose: {:?}", Vec::from_iter(index.loose_numbers().map(|n| (n.value, n.x0..n.x1, n.y))));
+    println!("next to '#': {:?}", Vec::from_iter(index.numbers_next_to(b'#').map(|n| n.value)));
+    println!("'*' with one number: {:?}", Vec::from_iter(index.gears(b'*', 1).map(|g| (index.symbols[g].x, index.symbols[g].y))));
+
+    println!();
+}
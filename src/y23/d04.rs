@@ -1,22 +1,26 @@
 use std::collections::VecDeque;
 
+use crate::util::chunks::{self, Split};
+use crate::util::lines;
+use crate::util::parse::Cursor;
+use crate::util::pattern::parse;
+
 
 fn part_1(input: &str) -> i32 {
-    input.lines().map(|line| {
+    lines::lines(input).map(|line| {
         let (_, line) = line.split_once(": ").unwrap();
         let (winning, have) = line.split_once(" | ").unwrap();
 
         let mut table = [false; 256];
-        for number in winning.split_whitespace() {
-            let number = number.parse::<u8>().unwrap();
-            table[number as usize] = true;
+        let mut winning = Cursor::new(winning);
+        while let Some(number) = winning.next_u64_in_line() {
+            table[number.unwrap() as usize] = true;
         }
 
         let mut num_matches = 0;
-        for number in have.split_whitespace() {
-            let number = number.parse::<u8>().unwrap();
-
-            if table[number as usize] {
+        let mut have = Cursor::new(have);
+        while let Some(number) = have.next_u64_in_line() {
+            if table[number.unwrap() as usize] {
                 num_matches += 1;
             }
         }
@@ -34,21 +38,20 @@ fn part_2(input: &str) -> i32 {
     let mut copies = VecDeque::new();
     copies.push_back(1);
 
-    input.lines().map(|line| {
+    lines::lines(input).map(|line| {
         let (_, line) = line.split_once(": ").unwrap();
         let (winning, have) = line.split_once(" | ").unwrap();
 
         let mut table = [false; 256];
-        for number in winning.split_whitespace() {
-            let number = number.parse::<u8>().unwrap();
-            table[number as usize] = true;
+        let mut winning = Cursor::new(winning);
+        while let Some(number) = winning.next_u64_in_line() {
+            table[number.unwrap() as usize] = true;
         }
 
         let mut num_matches = 0;
-        for number in have.split_whitespace() {
-            let number = number.parse::<u8>().unwrap();
-
-            if table[number as usize] {
+        let mut have = Cursor::new(have);
+        while let Some(number) = have.next_u64_in_line() {
+            if table[number.unwrap() as usize] {
                 num_matches += 1;
             }
         }
@@ -72,7 +75,77 @@ fn part_2(input: &str) -> i32 {
 }
 
 
-fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
+#[derive(Clone, Copy, Debug)]
+struct Card {
+    winning: u128,
+    have: u128,
+}
+
+impl Card {
+    #[inline]
+    fn num_matches(&self) -> u32 {
+        (self.winning & self.have).count_ones()
+    }
+}
+
+fn bits(numbers: &[u8]) -> u128 {
+    let mut result = 0;
+    for number in numbers {
+        assert!(*number < 128);
+        result |= 1 << number;
+    }
+    return result;
+}
+
+fn parse_cards(input: &str) -> Vec<Card> {
+    Vec::from_iter(lines::lines(input).map(|line| {
+        let (winning, have) = parse!(line, "Card " {_} ": " {Vec<u8>} " | " {Vec<u8>}).unwrap();
+        Card { winning: bits(&winning), have: bits(&have) }
+    }))
+}
+
+fn match_counts(input: &str) -> Vec<u32> {
+    Vec::from_iter(parse_cards(input).iter().map(Card::num_matches))
+}
+
+// final number of copies of each card.
+// card `i` adds its copies to the next `matches[i]` cards,
+// which is a range update, so we keep a difference array.
+fn copy_counts(matches: &[u32]) -> Vec<u64> {
+    let n = matches.len();
+
+    let mut delta = vec![0i64; n+1];
+    let mut extra = 0;
+    return Vec::from_iter(matches.iter().enumerate().map(|(i, m)| {
+        extra += delta[i];
+        let copies = 1 + extra;
+
+        let end = (i + 1 + *m as usize).min(n);
+        delta[i+1] += copies;
+        delta[end] -= copies;
+
+        copies as u64
+    }));
+}
+
+fn part_1_bits(input: &str) -> u64 {
+    match_counts(input).iter()
+    .map(|m| if *m > 0 { 1 << (m - 1) } else { 0 })
+    .sum()
+}
+
+fn part_2_bits(input: &str) -> u64 {
+    copy_counts(&match_counts(input)).iter().sum()
+}
+
+
+// part 2 isn't split up, copies carry over into the following cards.
+fn part_1_threaded(input: &str) -> i32 {
+    chunks::par_sum(input, Split::Lines, part_1)
+}
+
+
+fn run<T: core::fmt::Display>(name: &str, f: impl FnOnce(&str) -> T, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
     let dt = t0.elapsed();
@@ -81,11 +154,24 @@ fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
 }
 
 pub fn main() {
+    println!("-- day 04 --");
+
     run("part_1", part_1, include_str!("d04-test.txt"));
     run("part_1", part_1, include_str!("d04-prod.txt"));
 
     run("part_2", part_2, include_str!("d04-test.txt"));
     run("part_2", part_2, include_str!("d04-prod.txt"));
-}
 
+    run("part_1_bits", part_1_bits, include_str!("d04-test.txt"));
+    run("part_1_bits", part_1_bits, include_str!("d04-prod.txt"));
 
+    run("part_2_bits", part_2_bits, include_str!("d04-test.txt"));
+    run("part_2_bits", part_2_bits, include_str!("d04-prod.txt"));
+
+    run("part_1_threaded", part_1_threaded, include_str!("d04-prod.txt"));
+
+    let matches = match_counts(include_str!("d04-test.txt"));
+    println!("matches: {:?}, copies: {:?}", matches, copy_counts(&matches));
+
+    println!();
+}
@@ -1,27 +1,29 @@
+use crate::util::lines;
+use crate::util::parse::Cursor;
+use crate::util::pattern::parse;
+
+
 
 fn part_1(input: &str) -> i32 {
     let mut parts = input.split("\n\n");
     let seeds = parts.next().unwrap();
 
-    let mut seeds = Vec::from_iter(
-        seeds.split_once(": ").unwrap().1
-        .split_whitespace()
-        .map(|n| n.parse::<u32>().unwrap()));
+    let mut seeds = parse!(seeds, "seeds: " {Vec<u32>}).unwrap();
 
     let mut mapped = vec![false; seeds.len()];
 
     for map in parts {
-        let mut lines = map.lines();
+        let mut lines = lines::lines(map);
         lines.next();
 
         for x in &mut mapped { *x = false }
 
         for line in lines {
-            let mut numbers = line.split_whitespace();
-            let dst = numbers.next().unwrap().parse::<u32>().unwrap();
-            let src = numbers.next().unwrap().parse::<u32>().unwrap();
-            let len = numbers.next().unwrap().parse::<u32>().unwrap();
-            assert!(numbers.next().is_none());
+            let mut numbers = Cursor::new(line);
+            let dst = numbers.next_u64().unwrap() as u32;
+            let src = numbers.next_u64().unwrap() as u32;
+            let len = numbers.next_u64().unwrap() as u32;
+            assert!(numbers.next_u64_in_line().is_none());
 
             for (i, seed) in seeds.iter_mut().enumerate() {
                 if !mapped[i] && *seed >= src && *seed - src < len {
@@ -40,10 +42,7 @@ fn part_2(input: &str) -> i32 {
     let mut parts = input.split("\n\n");
     let seeds = parts.next().unwrap();
 
-    let seeds = Vec::from_iter(
-        seeds.split_once(": ").unwrap().1
-        .split_whitespace()
-        .map(|n| n.parse::<u64>().unwrap()));
+    let seeds = parse!(seeds, "seeds: " {Vec<u64>}).unwrap();
     assert!(seeds.len() % 2 == 0);
 
     let mut seeds = Vec::from_iter((0..seeds.len()/2).map(|i| (seeds[2*i], seeds[2*i+1])));
@@ -51,18 +50,18 @@ fn part_2(input: &str) -> i32 {
     let mut mapped = vec![false; seeds.len()];
 
     for map in parts {
-        let mut lines = map.lines();
+        let mut lines = lines::lines(map);
         lines.next();
 
         assert_eq!(seeds.len(), mapped.len());
         for x in &mut mapped { *x = false }
 
         for line in lines {
-            let mut numbers = line.split_whitespace();
-            let dst = numbers.next().unwrap().parse::<u64>().unwrap();
-            let src = numbers.next().unwrap().parse::<u64>().unwrap();
-            let len = numbers.next().unwrap().parse::<u64>().unwrap();
-            assert!(numbers.next().is_none());
+            let mut numbers = Cursor::new(line);
+            let dst = numbers.next_u64().unwrap();
+            let src = numbers.next_u64().unwrap();
+            let len = numbers.next_u64().unwrap();
+            assert!(numbers.next_u64_in_line().is_none());
 
             for i in 0..seeds.len() {
                 if mapped[i] { continue }
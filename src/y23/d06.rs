@@ -1,3 +1,5 @@
+use crate::util::lines;
+use crate::util::parse::Cursor;
 
 fn part_1(input: &str) -> i32 {
     /*
@@ -9,20 +11,11 @@ fn part_1(input: &str) -> i32 {
         t01 = ceil(tmax/2 +- sqrt(tmax^2/4 - dst))
     */
 
-    let mut lines = input.lines();
-    let ts = Vec::from_iter(
-        lines.next().unwrap()
-        .split_once(":").unwrap().1
-        .trim()
-        .split_whitespace()
-        .map(|n| n.parse::<i32>().unwrap()));
-
-    let ds = Vec::from_iter(
-        lines.next().unwrap()
-        .split_once(":").unwrap().1
-        .trim()
-        .split_whitespace()
-        .map(|n| n.parse::<i32>().unwrap()));
+    let mut c = Cursor::new(input);
+    c.expect_literal("Time:").unwrap();
+    let ts = c.u64s().unwrap();
+    c.expect_literal("Distance:").unwrap();
+    let ds = c.u64s().unwrap();
 
 
     let mut result = 1;
@@ -42,7 +35,7 @@ fn part_1(input: &str) -> i32 {
 
 
 fn part_2(input: &str) -> i32 {
-    let mut lines = input.lines();
+    let mut lines = lines::lines(input);
 
     let t = String::from_iter(
         lines.next().unwrap()
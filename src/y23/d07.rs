@@ -1,3 +1,6 @@
+use crate::util::chunks::{self, Split};
+use crate::util::lines;
+
 
 const NUM_CARDS: usize = 13;
 
@@ -10,10 +13,20 @@ const TYPE_1PAIR: usize = 1;
 const TYPE_HIGH:  usize = 0;
 const NUM_HAND_TYPES: usize = 7;
 
+const TYPE_NAMES: [&str; NUM_HAND_TYPES] = [
+    "high card",
+    "one pair",
+    "two pair",
+    "three of a kind",
+    "full house",
+    "four of a kind",
+    "five of a kind",
+];
+
 fn part_1(input: &str) -> i32 {
     let mut hands: [Vec<([u8; 5], u16)>; NUM_HAND_TYPES] = core::array::from_fn(|_| vec![]);
 
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (hand, bid) = line.split_once(" ").unwrap();
         assert!(hand.len() == 5);
 
@@ -110,7 +123,7 @@ fn part_1(input: &str) -> i32 {
 fn part_2(input: &str) -> i32 {
     let mut hands: [Vec<([u8; 5], u16)>; NUM_HAND_TYPES] = core::array::from_fn(|_| vec![]);
 
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (hand, bid) = line.split_once(" ").unwrap();
         assert!(hand.len() == 5);
 
@@ -219,7 +232,7 @@ fn part_2(input: &str) -> i32 {
 fn part_2_fast(input: &str) -> i32 {
     let mut hands: [Vec<([u8; 5], u16)>; NUM_HAND_TYPES] = core::array::from_fn(|_| vec![]);
 
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (hand, bid) = line.split_once(" ").unwrap();
         assert!(hand.len() == 5);
 
@@ -315,7 +328,7 @@ fn part_2_fast(input: &str) -> i32 {
 fn part_2_fast_isse(input: &str) -> i32 {
     let mut hands: [Vec<([u8; 5], u16)>; NUM_HAND_TYPES] = core::array::from_fn(|_| vec![]);
 
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (hand, bid) = line.split_once(" ").unwrap();
         assert!(hand.len() == 5);
 
@@ -389,7 +402,241 @@ fn part_2_fast_isse(input: &str) -> i32 {
 }
 
 
-fn run(name: &str, f: impl FnOnce(&str) -> i32, input: &str) {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TieBreak {
+    // compare card by card, in the order they were dealt.
+    InOrder,
+    // compare the most frequent cards first, like poker.
+    Sorted,
+}
+
+struct Rules {
+    // card value by byte, `u8::MAX` if not a card.
+    values: [u8; 256],
+    // cards from weakest to strongest.
+    cards: Vec<u8>,
+    wild: [bool; 256],
+    hand_size: usize,
+    tie_break: TieBreak,
+}
+
+impl Rules {
+    // `order` lists the cards from weakest to strongest.
+    fn new(order: &str, wild: &str, hand_size: usize, tie_break: TieBreak) -> Self {
+        let mut values = [u8::MAX; 256];
+        for (i, card) in order.bytes().enumerate() {
+            assert!(values[card as usize] == u8::MAX);
+            values[card as usize] = i as u8;
+        }
+
+        let mut wilds = [false; 256];
+        for card in wild.bytes() {
+            assert!(values[card as usize] != u8::MAX);
+            wilds[card as usize] = true;
+        }
+
+        Self { values, cards: order.as_bytes().to_vec(), wild: wilds, hand_size, tie_break }
+    }
+
+    fn part_1() -> Self {
+        Self::new("23456789TJQKA", "", 5, TieBreak::InOrder)
+    }
+
+    fn part_2() -> Self {
+        Self::new("J23456789TQKA", "J", 5, TieBreak::InOrder)
+    }
+
+    fn classify(&self, hand: &[u8]) -> usize {
+        let mut counts = vec![0u8; self.cards.len()];
+        let mut wilds = 0;
+        for card in hand.iter().copied() {
+            if self.wild[card as usize] {
+                wilds += 1;
+            }
+            else {
+                counts[self.values[card as usize] as usize] += 1;
+            }
+        }
+
+        let mut highest = 0;
+        let mut second = 0;
+        for count in counts.iter().copied() {
+            if count > highest {
+                second = highest;
+                highest = count;
+            }
+            else if count > second {
+                second = count;
+            }
+        }
+        // wildcards always do best joining the largest group.
+        highest += wilds;
+
+        match (highest, second) {
+            (5.., _) => TYPE_FIVE,
+            (4, _)   => TYPE_FOUR,
+            (3, 2..) => TYPE_HOUSE,
+            (3, _)   => TYPE_THREE,
+            (2, 2)   => TYPE_2PAIR,
+            (2, _)   => TYPE_1PAIR,
+            _        => TYPE_HIGH,
+        }
+    }
+
+    // the card the wildcards should become to get the best type.
+    // `None` if the hand has no wildcards.
+    fn best_substitute(&self, hand: &[u8]) -> Option<u8> {
+        if !hand.iter().any(|card| self.wild[*card as usize]) {
+            return None;
+        }
+
+        let mut counts = vec![0u8; self.cards.len()];
+        for card in hand.iter().copied() {
+            if !self.wild[card as usize] {
+                counts[self.values[card as usize] as usize] += 1;
+            }
+        }
+
+        // join the largest group, preferring the stronger card on ties.
+        // a hand of only wildcards becomes the strongest regular card,
+        // or the strongest card, if every card is wild.
+        let best = (0..self.cards.len())
+            .filter(|v| !self.wild[self.cards[*v] as usize])
+            .max_by_key(|v| (counts[*v], *v))
+            .unwrap_or(self.cards.len() - 1);
+        return Some(self.cards[best]);
+    }
+
+    fn tie_break_key(&self, hand: &[u8]) -> Vec<u8> {
+        let mut key = Vec::from_iter(hand.iter().map(|card| self.values[*card as usize]));
+
+        if self.tie_break == TieBreak::Sorted {
+            let mut counts = vec![0u8; self.cards.len()];
+            for v in key.iter().copied() {
+                counts[v as usize] += 1;
+            }
+            key.sort_unstable_by(|a, b|
+                counts[*b as usize].cmp(&counts[*a as usize]).then(b.cmp(a)));
+        }
+
+        return key;
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RankedHand<'a> {
+    hand: &'a str,
+    bid: u64,
+    ty: usize,
+    // 1 is the weakest hand.
+    rank: usize,
+}
+
+impl<'a> RankedHand<'a> {
+    fn winnings(&self) -> u64 {
+        self.rank as u64 * self.bid
+    }
+}
+
+fn rank_hands<'a>(input: &'a str, rules: &Rules) -> Vec<RankedHand<'a>> {
+    let mut hands = Vec::from_iter(lines::lines(input).map(|line| {
+        let (hand, bid) = line.split_once(" ").unwrap();
+        assert_eq!(hand.len(), rules.hand_size);
+        assert!(hand.bytes().all(|card| rules.values[card as usize] != u8::MAX));
+
+        let ty = rules.classify(hand.as_bytes());
+        let key = rules.tie_break_key(hand.as_bytes());
+        (ty, key, RankedHand { hand, bid: bid.parse().unwrap(), ty, rank: 0 })
+    }));
+
+    hands.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    return Vec::from_iter(hands.into_iter().enumerate().map(|(i, (_, _, hand))| {
+        RankedHand { rank: i + 1, ..hand }
+    }));
+}
+
+fn total_winnings(hands: &[RankedHand]) -> u64 {
+    hands.iter().map(|hand| hand.winnings()).sum()
+}
+
+#[derive(Clone, Debug)]
+struct Explanation<'a> {
+    hand: &'a str,
+    // what the wildcards become.
+    substitute: Option<u8>,
+    ty: &'static str,
+    rank: usize,
+    winnings: u64,
+}
+
+fn explain<'a>(input: &'a str, rules: &Rules) -> Vec<Explanation<'a>> {
+    Vec::from_iter(rank_hands(input, rules).iter().map(|hand| {
+        let substitute = rules.best_substitute(hand.hand.as_bytes());
+
+        if let Some(sub) = substitute {
+            let replaced = Vec::from_iter(hand.hand.bytes().map(|card|
+                if rules.wild[card as usize] { sub } else { card }));
+            debug_assert_eq!(rules.classify(&replaced), hand.ty);
+        }
+
+        Explanation {
+            hand: hand.hand,
+            substitute,
+            ty: TYPE_NAMES[hand.ty],
+            rank: hand.rank,
+            winnings: hand.winnings(),
+        }
+    }))
+}
+
+fn print_report(input: &str, rules: &Rules) {
+    let hands = explain(input, rules);
+
+    println!("{:>5}  {:<5}  {:<5}  {:<15}  {:>10}", "rank", "hand", "as", "type", "winnings");
+    for hand in hands.iter().rev() {
+        let replaced = match hand.substitute {
+            Some(sub) => String::from_iter(hand.hand.bytes().map(|card|
+                if rules.wild[card as usize] { sub as char } else { card as char })),
+            None => String::new(),
+        };
+        println!("{:>5}  {:<5}  {:<5}  {:<15}  {:>10}", hand.rank, hand.hand, replaced, hand.ty, hand.winnings);
+    }
+    println!("total: {}", hands.iter().map(|hand| hand.winnings).sum::<u64>());
+}
+
+
+fn part_1_rules(input: &str) -> u64 {
+    total_winnings(&rank_hands(input, &Rules::part_1()))
+}
+
+fn part_2_rules(input: &str) -> u64 {
+    total_winnings(&rank_hands(input, &Rules::part_2()))
+}
+
+
+// classify in parallel, rank once everything's merged.
+fn part_2_rules_threaded(input: &str) -> u64 {
+    let rules = Rules::part_2();
+
+    let chunks = chunks::par_map(input, chunks::default_count(input), Split::Lines, |chunk| {
+        Vec::from_iter(lines::lines(chunk).map(|line| {
+            let (hand, bid) = line.split_once(" ").unwrap();
+            assert_eq!(hand.len(), rules.hand_size);
+            let ty = rules.classify(hand.as_bytes());
+            let key = rules.tie_break_key(hand.as_bytes());
+            (ty, key, bid.parse::<u64>().unwrap())
+        }))
+    });
+
+    let mut hands = Vec::from_iter(chunks.into_iter().flatten());
+    hands.sort_unstable();
+
+    return hands.iter().enumerate().map(|(i, (_, _, bid))| (i as u64 + 1) * bid).sum();
+}
+
+
+fn run<T: core::fmt::Display>(name: &str, f: impl FnOnce(&str) -> T, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
     let dt = t0.elapsed();
@@ -412,6 +659,27 @@ pub fn main() {
     run("part_2_fast_isse", part_2_fast_isse, include_str!("d07-test.txt"));
     run("part_2_fast_isse", part_2_fast_isse, include_str!("d07-prod.txt"));
 
+    run("part_1_rules", part_1_rules, include_str!("d07-test.txt"));
+    run("part_1_rules", part_1_rules, include_str!("d07-prod.txt"));
+
+    run("part_2_rules", part_2_rules, include_str!("d07-test.txt"));
+    run("part_2_rules", part_2_rules, include_str!("d07-prod.txt"));
+
+    run("part_2_rules_threaded", part_2_rules_threaded, include_str!("d07-test.txt"));
+    run("part_2_rules_threaded", part_2_rules_threaded, include_str!("d07-prod.txt"));
+
+    let poker = Rules::new("23456789TJQKA", "", 5, TieBreak::Sorted);
+    run("poker", |i| total_winnings(&rank_hands(i, &poker)), include_str!("d07-test.txt"));
+    for hand in rank_hands(include_str!("d07-test.txt"), &poker) {
+        println!("  {} type {} rank {}", hand.hand, hand.ty, hand.rank);
+    }
+
+    print_report(include_str!("d07-test.txt"), &Rules::part_2());
+    print_report("JJJJJ 7\nKKJKK 3\n", &Rules::part_2());
+    for hand in explain(include_str!("d07-prod.txt"), &Rules::part_2()).iter().rev().take(3) {
+        println!("  {} -> {:?} {} rank {} wins {}", hand.hand, hand.substitute.map(|c| c as char), hand.ty, hand.rank, hand.winnings);
+    }
+
     println!();
 }
 
@@ -1,3 +1,5 @@
+use crate::util::lines;
+use crate::util::pattern::parse;
 use std::collections::HashMap;
 
 
@@ -5,12 +7,8 @@ fn part_1(input: &str) -> u64 {
     let (instrs_str, table_str) = input.split_once("\n\n").unwrap();
 
     let mut table = HashMap::new();
-    for line in table_str.lines() {
-        let (k, v) = line.split_once(" = ").unwrap();
-
-        let v = &v[1..];
-        let v = &v[..v.len()-1];
-        let (l, r) = v.split_once(", ").unwrap();
+    for line in lines::lines(table_str) {
+        let (k, l, r) = parse!(line, {&str} " = (" {&str} ", " {&str} ")").unwrap();
 
         let none = table.insert(k, [l, r]);
         assert!(none.is_none());
@@ -38,12 +36,8 @@ fn part_2(input: &str) -> u64 {
     //let t0 = std::time::Instant::now();
     let mut ats = vec![];
     let mut table = HashMap::new();
-    for line in table_str.lines() {
-        let (k, v) = line.split_once(" = ").unwrap();
-
-        let v = &v[1..];
-        let v = &v[..v.len()-1];
-        let (l, r) = v.split_once(", ").unwrap();
+    for line in lines::lines(table_str) {
+        let (k, l, r) = parse!(line, {&str} " = (" {&str} ", " {&str} ")").unwrap();
 
         let none = table.insert(k, [l, r]);
         assert!(none.is_none());
@@ -104,14 +98,14 @@ fn part_2_fast(input: &str) -> u64 {
         let k = k.as_bytes();
         assert_eq!(k.len(), 3);
           convert_char(k[0]) << 2*5
-        | convert_char(k[1]) << 1*5
-        | convert_char(k[2]) << 0*5
+        | convert_char(k[1]) << 5
+        | convert_char(k[2])
     }
 
     //let t0 = std::time::Instant::now();
     let mut ats = vec![];
     let mut table = vec![[0, 0]; 32*32*32];
-    for line in table_str.lines() {
+    for line in lines::lines(table_str) {
         let (k, v) = line.split_once(" = ").unwrap();
 
         let v = &v[1..];
@@ -1,13 +1,15 @@
 use itertools::Itertools;
 
+use crate::util::chunks::{self, Split};
+use crate::util::lines;
+use crate::util::parse::Cursor;
+
 
 fn part_1(input: &str) -> u64 {
     let mut result = 0;
 
-    for line in input.lines() {
-        let mut seq = Vec::from_iter(
-            line.split_whitespace()
-            .map(|n| n.parse::<i32>().unwrap()));
+    for line in lines::lines(input) {
+        let mut seq = Cursor::new(line).i64s().unwrap();
 
         let mut lasts = vec![];
         while !seq.iter().all(|n| *n == 0) {
@@ -15,7 +17,7 @@ fn part_1(input: &str) -> u64 {
             seq = Vec::from_iter(seq.iter().tuple_windows().map(|(a, b)| b - a));
         }
 
-        result += lasts.iter().sum::<i32>() as i64;
+        result += lasts.iter().sum::<i64>();
     }
 
     return result as u64;
@@ -25,10 +27,8 @@ fn part_1(input: &str) -> u64 {
 fn part_2(input: &str) -> u64 {
     let mut result = 0;
 
-    for line in input.lines() {
-        let mut seq = Vec::from_iter(
-            line.split_whitespace()
-            .map(|n| n.parse::<i32>().unwrap()));
+    for line in lines::lines(input) {
+        let mut seq = Cursor::new(line).i64s().unwrap();
 
         let mut firsts = vec![];
         while !seq.iter().all(|n| *n == 0) {
@@ -40,13 +40,23 @@ fn part_2(input: &str) -> u64 {
         for f in firsts.iter().copied().rev() {
             n = f - n;
         }
-        result += n as i64;
+        result += n;
     }
 
     return result as u64;
 }
 
 
+fn part_1_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Lines, part_1)
+}
+
+
+fn part_2_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Lines, part_2)
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -64,6 +74,9 @@ pub fn main() {
     run("part_2", part_2, include_str!("d09-test.txt"));
     run("part_2", part_2, include_str!("d09-prod.txt"));
 
+    run("part_1_threaded", part_1_threaded, include_str!("d09-prod.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d09-prod.txt"));
+
     println!();
 }
 
@@ -173,6 +173,231 @@ impl<'a> core::ops::Index<(usize, usize)> for Grid<'a> {
     }
 }
 
+impl<'a> Grid<'a> {
+    // directions in which (x, y) is connected to a neighbour
+    // that is also connected back to (x, y).
+    #[inline]
+    fn links(&self, x: usize, y: usize) -> u32 {
+        let at = Step::options(self[(x, y)]);
+
+        let mut result = 0;
+        if Step::Up.is_in(at) && y > 0
+        && Step::Down.is_in(Step::options(self[(x, y-1)])) {
+            result |= Step::Up as u32;
+        }
+        if Step::Left.is_in(at) && x > 0
+        && Step::Right.is_in(Step::options(self[(x-1, y)])) {
+            result |= Step::Left as u32;
+        }
+        if Step::Down.is_in(at) && y < self.h-1
+        && Step::Up.is_in(Step::options(self[(x, y+1)])) {
+            result |= Step::Down as u32;
+        }
+        if Step::Right.is_in(at) && x < self.w-1
+        && Step::Left.is_in(Step::options(self[(x+1, y)])) {
+            result |= Step::Right as u32;
+        }
+        return result;
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    Ground,
+    Loop,
+    Junk,
+}
+
+struct Analysis {
+    w: usize,
+    s: usize,
+    h: usize,
+
+    // indexed like the grid, `y*s + x`.
+    tiles: Vec<Tile>,
+    links: Vec<u32>,
+
+    // tiles of each closed loop & each dead-end pipe segment.
+    loops: Vec<Vec<(usize, usize)>>,
+    dead_ends: Vec<Vec<(usize, usize)>>,
+
+    start: (usize, usize),
+    start_links: u32,
+    start_loop: Option<usize>,
+}
+
+impl Analysis {
+    fn start_ambiguous(&self) -> bool {
+        self.start_links.count_ones() > 2
+    }
+
+    fn count(&self, tile: Tile) -> usize {
+        (0..self.h).map(|y| {
+            (0..self.w).filter(|x| self.tiles[y*self.s + x] == tile).count()
+        }).sum()
+    }
+
+    // bfs distance to the farthest loop tile reachable from `S`.
+    // unlike `len/2`, this doesn't assume the loop is a simple cycle.
+    fn farthest_from_start(&self) -> Option<u64> {
+        self.start_loop?;
+
+        const STEPS: [Step; 4] = [Step::Up, Step::Left, Step::Down, Step::Right];
+
+        let mut dist = vec![u64::MAX; self.s*self.h];
+        let mut queue = std::collections::VecDeque::new();
+        let (sx, sy) = self.start;
+        dist[sy*self.s + sx] = 0;
+        queue.push_back((sx, sy));
+
+        let mut result = 0;
+        while let Some((x, y)) = queue.pop_front() {
+            let i = y*self.s + x;
+            result = result.max(dist[i]);
+
+            for step in STEPS {
+                if !step.is_in(self.links[i]) { continue }
+
+                let (mut nx, mut ny) = (x, y);
+                step.apply(&mut nx, &mut ny);
+
+                let j = ny*self.s + nx;
+                if self.tiles[j] == Tile::Loop && dist[j] == u64::MAX {
+                    dist[j] = dist[i] + 1;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        return Some(result);
+    }
+}
+
+fn analyze(input: &str) -> Analysis {
+    let (grid, start) = Grid::new(input);
+
+    const STEPS: [Step; 4] = [Step::Up, Step::Left, Step::Down, Step::Right];
+
+    let mut links = vec![0u32; grid.s*grid.h];
+    let mut degree = vec![0u32; grid.s*grid.h];
+    let mut on_loop = vec![false; grid.s*grid.h];
+    for y in 0..grid.h {
+        for x in 0..grid.w {
+            let i = y*grid.s + x;
+            links[i] = grid.links(x, y);
+            degree[i] = links[i].count_ones();
+            on_loop[i] = Step::options(grid[(x, y)]) != 0;
+        }
+    }
+
+    // peel off dead ends.
+    // whatever has two or more links left afterwards is on a loop.
+    let mut stack = vec![];
+    for y in 0..grid.h {
+        for x in 0..grid.w {
+            let i = y*grid.s + x;
+            if on_loop[i] && degree[i] < 2 {
+                stack.push((x, y));
+            }
+        }
+    }
+    while let Some((x, y)) = stack.pop() {
+        let i = y*grid.s + x;
+        if !on_loop[i] { continue }
+        on_loop[i] = false;
+
+        for step in STEPS {
+            if !step.is_in(links[i]) { continue }
+
+            let (mut nx, mut ny) = (x, y);
+            step.apply(&mut nx, &mut ny);
+
+            let j = ny*grid.s + nx;
+            if on_loop[j] {
+                degree[j] -= 1;
+                if degree[j] < 2 {
+                    stack.push((nx, ny));
+                }
+            }
+        }
+    }
+
+    let mut tiles = vec![Tile::Ground; grid.s*grid.h];
+    let mut component = vec![usize::MAX; grid.s*grid.h];
+    let mut loops = vec![];
+    let mut dead_ends = vec![];
+    for y in 0..grid.h {
+        for x in 0..grid.w {
+            let i = y*grid.s + x;
+            if Step::options(grid[(x, y)]) == 0 || component[i] != usize::MAX {
+                continue;
+            }
+
+            // flood fill, staying on the same side of the loop/junk split.
+            let is_loop = on_loop[i];
+            let id = if is_loop { loops.len() } else { dead_ends.len() };
+
+            let mut segment = vec![];
+            component[i] = id;
+            stack.push((x, y));
+            while let Some((x, y)) = stack.pop() {
+                let i = y*grid.s + x;
+                tiles[i] = if is_loop { Tile::Loop } else { Tile::Junk };
+                segment.push((x, y));
+
+                for step in STEPS {
+                    if !step.is_in(links[i]) { continue }
+
+                    let (mut nx, mut ny) = (x, y);
+                    step.apply(&mut nx, &mut ny);
+
+                    let j = ny*grid.s + nx;
+                    if on_loop[j] == is_loop && component[j] == usize::MAX {
+                        component[j] = id;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            if is_loop { loops.push(segment) }
+            else       { dead_ends.push(segment) }
+        }
+    }
+
+    let start_i = start.1*grid.s + start.0;
+    let start_loop = on_loop[start_i].then(|| component[start_i]);
+    let start_links = links[start_i];
+
+    return Analysis {
+        w: grid.w, s: grid.s, h: grid.h,
+        tiles,
+        links,
+        loops,
+        dead_ends,
+        start,
+        start_links,
+        start_loop,
+    };
+}
+
+fn print_analysis(name: &str, input: &str) {
+    let a = analyze(input);
+
+    println!("{name}: {} loops, {} dead ends, tiles: {} loop, {} junk, {} ground",
+        a.loops.len(), a.dead_ends.len(),
+        a.count(Tile::Loop), a.count(Tile::Junk), a.count(Tile::Ground));
+
+    let (sx, sy) = a.start;
+    if a.start_ambiguous() {
+        println!("  S at ({sx}, {sy}) is ambiguous: {} connectable neighbours", a.start_links.count_ones());
+    }
+    match a.start_loop {
+        Some(l) => println!("  S at ({sx}, {sy}) is on a loop of length {}", a.loops[l].len()),
+        None    => println!("  S at ({sx}, {sy}) is not on a loop"),
+    }
+}
+
+
 fn part_1(input: &str) -> u64 {
     let (grid, (start_x, start_y)) = Grid::new(input);
 
@@ -390,6 +615,11 @@ fn part_2_fast(input: &str) -> u64 {
 }
 
 
+fn part_1_analysis(input: &str) -> u64 {
+    return analyze(input).farthest_from_start().unwrap();
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -404,6 +634,9 @@ pub fn main() {
     run("part_1", part_1, include_str!("d10-test.txt"));
     run("part_1", part_1, include_str!("d10-prod.txt"));
 
+    run("part_1_analysis", part_1_analysis, include_str!("d10-test.txt"));
+    run("part_1_analysis", part_1_analysis, include_str!("d10-prod.txt"));
+
     run("part_2", part_2, include_str!("d10-test.txt"));
     run("part_2", part_2, include_str!("d10-test-2.txt"));
     run("part_2", part_2, include_str!("d10-test-3.txt"));
@@ -414,6 +647,11 @@ pub fn main() {
     run("part_2_fast", part_2_fast, include_str!("d10-test-3.txt"));
     run("part_2_fast", part_2_fast, include_str!("d10-prod.txt"));
 
+    print_analysis("analysis", include_str!("d10-test.txt"));
+    print_analysis("analysis", include_str!("d10-test-2.txt"));
+    print_analysis("analysis", include_str!("d10-test-3.txt"));
+    print_analysis("analysis", include_str!("d10-prod.txt"));
+
     println!();
 }
 
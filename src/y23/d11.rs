@@ -70,6 +70,138 @@ fn solution(input: &str, grow: i64) -> u64 {
 }
 
 
+#[derive(Clone, Debug)]
+enum Expansion {
+    // every empty row & column grows by `n`.
+    Uniform(i64),
+    RowsOnly(i64),
+    ColumnsOnly(i64),
+    // empty row `y` grows by `rows[y]`, empty column `x` by `cols[x]`.
+    PerLine { rows: Vec<i64>, cols: Vec<i64> },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Metric {
+    Manhattan,
+    Chebyshev,
+}
+
+// galaxy positions after expansion.
+fn expand(input: &str, expansion: &Expansion) -> Vec<(i64, i64)> {
+    let g = Grid::new(input);
+
+    let mut has_galaxy_x = vec![false; g.w];
+    let mut has_galaxy_y = vec![false; g.h];
+    for y in 0..g.h {
+        for x in 0..g.w {
+            if g.data[y*g.s + x] == b'#' {
+                has_galaxy_x[x] = true;
+                has_galaxy_y[y] = true;
+            }
+        }
+    }
+
+    let grow_x = |x: usize| match expansion {
+        Expansion::Uniform(n) | Expansion::ColumnsOnly(n) => *n,
+        Expansion::RowsOnly(_) => 0,
+        Expansion::PerLine { cols, .. } => cols[x],
+    };
+    let grow_y = |y: usize| match expansion {
+        Expansion::Uniform(n) | Expansion::RowsOnly(n) => *n,
+        Expansion::ColumnsOnly(_) => 0,
+        Expansion::PerLine { rows, .. } => rows[y],
+    };
+
+    // expanded coordinate of each row & column.
+    let pos_x = {
+        let mut p = 0;
+        Vec::from_iter((0..g.w).map(|x| {
+            let result = p;
+            p += 1 + if has_galaxy_x[x] { 0 } else { grow_x(x) };
+            result
+        }))
+    };
+    let pos_y = {
+        let mut p = 0;
+        Vec::from_iter((0..g.h).map(|y| {
+            let result = p;
+            p += 1 + if has_galaxy_y[y] { 0 } else { grow_y(y) };
+            result
+        }))
+    };
+
+    let mut galaxies = vec![];
+    for y in 0..g.h {
+        for x in 0..g.w {
+            if g.data[y*g.s + x] == b'#' {
+                galaxies.push((pos_x[x], pos_y[y]));
+            }
+        }
+    }
+    return galaxies;
+}
+
+// sum of |a - b| over all pairs.
+// once sorted, the k-th value is bigger than the k values before it.
+fn axis_sum(values: &mut [i64]) -> i64 {
+    values.sort_unstable();
+
+    let mut result = 0;
+    let mut prefix = 0;
+    for (k, v) in values.iter().copied().enumerate() {
+        result += v*k as i64 - prefix;
+        prefix += v;
+    }
+    return result;
+}
+
+fn distance(metric: Metric, (x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> i64 {
+    let dx = (x2 - x1).abs();
+    let dy = (y2 - y1).abs();
+    match metric {
+        Metric::Manhattan => dx + dy,
+        Metric::Chebyshev => dx.max(dy),
+    }
+}
+
+fn total_distance(galaxies: &[(i64, i64)], metric: Metric) -> u64 {
+    let (mut us, mut vs): (Vec<i64>, Vec<i64>) = match metric {
+        Metric::Manhattan => galaxies.iter().copied().unzip(),
+
+        // max(|dx|, |dy|) = (|du| + |dv|)/2 with u = x+y, v = x-y.
+        Metric::Chebyshev => galaxies.iter().map(|(x, y)| (x + y, x - y)).unzip(),
+    };
+
+    let result = axis_sum(&mut us) + axis_sum(&mut vs);
+    match metric {
+        Metric::Manhattan => result as u64,
+        Metric::Chebyshev => (result / 2) as u64,
+    }
+}
+
+fn distance_matrix(galaxies: &[(i64, i64)], metric: Metric) -> Vec<Vec<u64>> {
+    Vec::from_iter(galaxies.iter().map(|a| {
+        Vec::from_iter(galaxies.iter().map(|b| distance(metric, *a, *b) as u64))
+    }))
+}
+
+// (nearest, farthest), as `(i, j, distance)`.
+fn extreme_pairs(galaxies: &[(i64, i64)], metric: Metric) -> ((usize, usize, u64), (usize, usize, u64)) {
+    assert!(galaxies.len() >= 2);
+
+    let mut nearest = (0, 1, u64::MAX);
+    let mut farthest = (0, 1, 0);
+    for i in 0..galaxies.len() {
+        for j in (i+1)..galaxies.len() {
+            let d = distance(metric, galaxies[i], galaxies[j]) as u64;
+            if d < nearest.2  { nearest  = (i, j, d) }
+            if d > farthest.2 { farthest = (i, j, d) }
+        }
+    }
+    return (nearest, farthest);
+}
+
+
 fn part_1(input: &str) -> u64 {
     solution(input, 2-1)
 }
@@ -80,6 +212,24 @@ fn part_2(input: &str) -> u64 {
 }
 
 
+fn part_1_fast(input: &str) -> u64 {
+    total_distance(&expand(input, &Expansion::Uniform(2-1)), Metric::Manhattan)
+}
+
+
+fn part_2_fast(input: &str) -> u64 {
+    total_distance(&expand(input, &Expansion::Uniform(1_000_000-1)), Metric::Manhattan)
+}
+
+
+fn print_extremes(name: &str, input: &str, expansion: &Expansion, metric: Metric) {
+    let galaxies = expand(input, expansion);
+    let ((ni, nj, nd), (fi, fj, fd)) = extreme_pairs(&galaxies, metric);
+    println!("{name}: total {}, nearest {}-{}: {nd}, farthest {}-{}: {fd}",
+        total_distance(&galaxies, metric), ni+1, nj+1, fi+1, fj+1);
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -97,6 +247,21 @@ pub fn main() {
     run("part_2", part_2, include_str!("d11-test.txt"));
     run("part_2", part_2, include_str!("d11-prod.txt"));
 
+    run("part_1_fast", part_1_fast, include_str!("d11-test.txt"));
+    run("part_1_fast", part_1_fast, include_str!("d11-prod.txt"));
+
+    run("part_2_fast", part_2_fast, include_str!("d11-test.txt"));
+    run("part_2_fast", part_2_fast, include_str!("d11-prod.txt"));
+
+    let test = include_str!("d11-test.txt");
+    print_extremes("rows only",   test, &Expansion::RowsOnly(1),    Metric::Manhattan);
+    print_extremes("cols only",   test, &Expansion::ColumnsOnly(1), Metric::Manhattan);
+    print_extremes("chebyshev",   test, &Expansion::Uniform(1),     Metric::Chebyshev);
+    print_extremes("per line", test, &Expansion::PerLine { rows: (0..10).collect(), cols: vec![1; 10] }, Metric::Manhattan);
+
+    let matrix = distance_matrix(&expand(test, &Expansion::Uniform(1)), Metric::Manhattan);
+    println!("distance 5-9: {}, 1-7: {}, 3-6: {}, 8-9: {}", matrix[4][8], matrix[0][6], matrix[2][5], matrix[7][8]);
+
     println!();
 }
 
@@ -1,11 +1,17 @@
 use std::collections::HashMap;
 
+use crate::util::chunks::{self, Split};
+use crate::util::bits::bit_pack;
+use crate::util::lines;
+use crate::util::memo::{MemoTable, Replace, Identity};
+use crate::util::parse::Cursor;
+
 
 fn part_1(input: &str) -> u64 {
     let mut result = 0;
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (row, pattern) = line.split_once(" ").unwrap();
-        let pattern = Vec::from_iter(pattern.split(",").map(|n| n.parse::<u32>().unwrap()));
+        let pattern = Vec::from_iter(Cursor::new(pattern).list_u64(",").unwrap().into_iter().map(|n| n as u32));
 
         fn rec(row: &[u8], pattern: &[u32]) -> u64 {
             if pattern.len() == 0 {
@@ -47,9 +53,9 @@ fn part_1(input: &str) -> u64 {
 
 fn part_2(input: &str) -> u64 {
     let mut result = 0;
-    for line in input.lines() {
+    for line in lines::lines(input) {
         let (row, pattern) = line.split_once(" ").unwrap();
-        let pattern = Vec::from_iter(pattern.split(",").map(|n| n.parse::<u32>().unwrap()));
+        let pattern = Vec::from_iter(Cursor::new(pattern).list_u64(",").unwrap().into_iter().map(|n| n as u32));
 
         let row = {
             let mut result = String::new();
@@ -116,6 +122,100 @@ fn part_2(input: &str) -> u64 {
 }
 
 
+const PATTERN_BITS: u32 = 6;
+
+bit_pack! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Key: u32 {
+        pattern_i: usize = PATTERN_BITS,
+        row_i:     usize = 32 - PATTERN_BITS,
+    }
+}
+
+// `part_2` with a `MemoTable`, keyed by the packed `(pattern_i, row_i)`.
+// the keys are below `(row.len() + 2) << PATTERN_BITS`, so the table is sized
+// to fit them all, and with `Identity` each gets its own slot and nothing is evicted.
+// a longer pattern doesn't fit the key, and `to_bits` panics.
+fn part_2_table(input: &str) -> u64 {
+    let mut result = 0;
+    for line in lines::lines(input) {
+        let (row, pattern) = line.split_once(" ").unwrap();
+        let pattern = Vec::from_iter(Cursor::new(pattern).list_u64(",").unwrap().into_iter().map(|n| n as u32));
+
+        let row = [row; 5].join("?");
+        let pattern = pattern.repeat(5);
+
+        struct Search<'a> {
+            memo: MemoTable<u64, Identity>,
+            full_pattern: &'a [u32],
+        }
+
+        impl<'a> Search<'a> {
+            fn rec(&mut self, row: &[u8], row_i: usize, pattern_i: usize) -> u64 {
+                let key = Key { pattern_i, row_i }.to_bits() as u64;
+                if let Some(result) = self.memo.get(key) {
+                    return result;
+                }
+
+                let result = self.rec_core(row, row_i, pattern_i);
+                self.memo.insert(key, result);
+                return result;
+            }
+
+            fn rec_core(&mut self, row: &[u8], row_i: usize, pattern_i: usize) -> u64 {
+                let pattern = &self.full_pattern[pattern_i..];
+                if pattern.len() == 0 {
+                    return row.iter().all(|s| *s == b'.' || *s == b'?') as u64;
+                }
+
+                let n = pattern[0] as usize;
+                let rest = &pattern[1..];
+
+                let mut has_spring = false;
+                let mut result = 0;
+                for i in 0..row.len() {
+                    if i+n > row.len() { break }
+
+                    if has_spring { break }
+                    has_spring = row[i] == b'#';
+
+                    if !row[i..i+n].iter().all(|s| *s == b'#' || *s == b'?') {
+                        continue;
+                    }
+
+                    if i+n == row.len() {
+                        if rest.len() == 0 {
+                            result += 1;
+                        }
+                    }
+                    else if row[i+n] != b'#' {
+                        result += self.rec(&row[i+n+1..], row_i+i+n+1, pattern_i+1);
+                    }
+                }
+                return result;
+            }
+        }
+
+        let mut search = Search {
+            memo: MemoTable::new((row.len() + 2) << PATTERN_BITS, Replace::Always),
+            full_pattern: &pattern,
+        };
+        result += search.rec(row.as_bytes(), 0, 0);
+    }
+    return result;
+}
+
+
+fn part_1_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Lines, part_1)
+}
+
+
+fn part_2_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Lines, part_2)
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -133,6 +233,12 @@ pub fn main() {
     run("part_2", part_2, include_str!("d12-test.txt"));
     run("part_2", part_2, include_str!("d12-prod.txt"));
 
+    run("part_2_table", part_2_table, include_str!("d12-test.txt"));
+    run("part_2_table", part_2_table, include_str!("d12-prod.txt"));
+
+    run("part_1_threaded", part_1_threaded, include_str!("d12-prod.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d12-prod.txt"));
+
     println!();
 }
 
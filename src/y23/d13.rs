@@ -1,3 +1,5 @@
+use crate::util::chunks::{self, Split};
+
 
 struct Grid<'a> {
     data: &'a [u8],
@@ -131,6 +133,16 @@ fn part_2(input: &str) -> u64 {
 }
 
 
+fn part_1_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Paragraphs, part_1)
+}
+
+
+fn part_2_threaded(input: &str) -> u64 {
+    chunks::par_sum(input, Split::Paragraphs, part_2)
+}
+
+
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
     let result = f(input);
@@ -148,6 +160,9 @@ pub fn main() {
     run("part_2", part_2, include_str!("d13-test.txt"));
     run("part_2", part_2, include_str!("d13-prod.txt"));
 
+    run("part_1_threaded", part_1_threaded, include_str!("d13-prod.txt"));
+    run("part_2_threaded", part_2_threaded, include_str!("d13-prod.txt"));
+
     println!();
 }
 
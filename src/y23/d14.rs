@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use crate::util::bits::bit_pack;
+use crate::util::memo::{MemoTable, Replace, Fx};
+
 
 struct Grid<'a> {
     data: &'a [u8],
@@ -54,87 +57,87 @@ fn part_1(input: &str) -> u64 {
 }
 
 
-fn part_2(input: &str) -> u64 {
-    let g = Grid::new(input);
-
-    let mut buf = input.as_bytes().to_vec();
-
-    fn cycle(buf: &mut [u8], w: usize, s: usize, h: usize) {
-        // north.
-        for x in 0..w {
-            let mut y_stop = 0;
-            for y in 0..h {
-                let at = buf[s*y + x];
-                if at == b'O' {
-                    if y_stop != y {
-                        buf[s*y_stop + x] = b'O';
-                        buf[s*y      + x] = b'.';
-                    }
-                    y_stop += 1;
-                }
-                if at == b'#' {
-                    y_stop = y + 1;
+fn cycle(buf: &mut [u8], w: usize, s: usize, h: usize) {
+    // north.
+    for x in 0..w {
+        let mut y_stop = 0;
+        for y in 0..h {
+            let at = buf[s*y + x];
+            if at == b'O' {
+                if y_stop != y {
+                    buf[s*y_stop + x] = b'O';
+                    buf[s*y      + x] = b'.';
                 }
+                y_stop += 1;
+            }
+            if at == b'#' {
+                y_stop = y + 1;
             }
         }
+    }
 
-        // west.
-        for y in 0..h {
-            let mut x_stop = 0;
-            for x in 0..w {
-                let at = buf[s*y + x];
-                if at == b'O' {
-                    if x_stop != x {
-                        buf[s*y + x_stop] = b'O';
-                        buf[s*y + x     ] = b'.';
-                    }
-                    x_stop += 1;
-                }
-                if at == b'#' {
-                    x_stop = x + 1;
+    // west.
+    for y in 0..h {
+        let mut x_stop = 0;
+        for x in 0..w {
+            let at = buf[s*y + x];
+            if at == b'O' {
+                if x_stop != x {
+                    buf[s*y + x_stop] = b'O';
+                    buf[s*y + x     ] = b'.';
                 }
+                x_stop += 1;
+            }
+            if at == b'#' {
+                x_stop = x + 1;
             }
         }
+    }
 
-        // south.
-        for x in 0..w {
-            let mut y_stop = h-1;
-            for y in (0..h).rev() {
-                let at = buf[s*y + x];
-                if at == b'O' {
-                    if y_stop != y {
-                        buf[s*y_stop + x] = b'O';
-                        buf[s*y      + x] = b'.';
-                    }
-                    y_stop = y_stop.wrapping_sub(1);
-                }
-                if at == b'#' {
-                    y_stop = y.wrapping_sub(1);
+    // south.
+    for x in 0..w {
+        let mut y_stop = h-1;
+        for y in (0..h).rev() {
+            let at = buf[s*y + x];
+            if at == b'O' {
+                if y_stop != y {
+                    buf[s*y_stop + x] = b'O';
+                    buf[s*y      + x] = b'.';
                 }
+                y_stop = y_stop.wrapping_sub(1);
+            }
+            if at == b'#' {
+                y_stop = y.wrapping_sub(1);
             }
         }
+    }
 
-        // east.
-        for y in 0..h {
-            let mut x_stop = w-1;
-            for x in (0..w).rev() {
-                let at = buf[s*y + x];
-                if at == b'O' {
-                    if x_stop != x {
-                        buf[s*y + x_stop] = b'O';
-                        buf[s*y + x     ] = b'.';
-                    }
-                    x_stop = x_stop.wrapping_sub(1);
-                }
-                if at == b'#' {
-                    x_stop = x.wrapping_sub(1);
+    // east.
+    for y in 0..h {
+        let mut x_stop = w-1;
+        for x in (0..w).rev() {
+            let at = buf[s*y + x];
+            if at == b'O' {
+                if x_stop != x {
+                    buf[s*y + x_stop] = b'O';
+                    buf[s*y + x     ] = b'.';
                 }
+                x_stop = x_stop.wrapping_sub(1);
+            }
+            if at == b'#' {
+                x_stop = x.wrapping_sub(1);
             }
         }
-
-        //for y in 0..h { println!("{}", core::str::from_utf8(&buf[y*s..y*s+w]).unwrap()) } println!()
     }
 
+    //for y in 0..h { println!("{}", core::str::from_utf8(&buf[y*s..y*s+w]).unwrap()) } println!()
+}
+
+fn part_2(input: &str) -> u64 {
+    let g = Grid::new(input);
+
+    let mut buf = input.as_bytes().to_vec();
+
     let mut n = 0u64;
     let mut visited = HashMap::new();
     loop {
@@ -160,6 +163,81 @@ fn part_2(input: &str) -> u64 {
     }
 }
 
+fn north_load(buf: &[u8], w: usize, s: usize, h: usize) -> u64 {
+    return (0..h).map(|y| {
+        ((0..w).filter(|x| buf[y*s + x] == b'O').count() * (h - y)) as u64
+    }).sum();
+}
+
+// the fingerprint of the rocks after a cycle:
+// the north load, and the top bits of the grid's fxhash.
+bit_pack! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Rocks: u64 {
+        load: u64 = 24,
+        hash: u64 = 40,
+    }
+}
+
+// same as `part_2`, but the table is keyed by a 64 bit `Rocks` fingerprint.
+// a hit is checked against the stored grid, so a collision only costs a search.
+// a grid that doesn't fit in the table grows it, so none are forgotten.
+fn part_2_table(input: &str) -> u64 {
+    let g = Grid::new(input);
+
+    let mut buf = input.as_bytes().to_vec();
+
+    let mut n = 0u64;
+    // `grids[i]` is the grid after `i+1` cycles, `keys[i]` its fingerprint.
+    let mut grids: Vec<Vec<u8>> = vec![];
+    let mut keys = vec![];
+    let mut capacity = 1024;
+    let mut visited = MemoTable::<u64, Fx>::new(capacity, Replace::Never);
+    loop {
+        cycle(&mut buf, g.w, g.s, g.h);
+        n += 1;
+
+        let load = north_load(&buf, g.w, g.s, g.h);
+        let key = Rocks { load, hash: Fx::hash_bytes(&buf) >> 24 }.to_bits();
+        let entry = visited.get(key);
+        let old_n = match entry {
+            Some(old_n) if grids[old_n as usize - 1] == buf => Some(old_n),
+            // another grid with the same fingerprint, so search them all.
+            Some(_) => grids.iter().position(|grid| *grid == buf).map(|i| i as u64 + 1),
+            None => None,
+        };
+
+        if let Some(old_n) = old_n {
+            let mut remaining = 1_000_000_000;
+            remaining -= n;
+            remaining %= n - old_n;
+
+            for _ in 0..remaining {
+                cycle(&mut buf, g.w, g.s, g.h);
+            }
+
+            return north_load(&buf, g.w, g.s, g.h);
+        }
+
+        grids.push(buf.clone());
+        keys.push(key);
+        if entry.is_some() {
+            continue;
+        }
+
+        visited.insert(key, n);
+        while visited.stats().rejects > 0 {
+            capacity *= 2;
+            visited = MemoTable::new(capacity, Replace::Never);
+            for (i, key) in keys.iter().enumerate() {
+                if visited.get(*key).is_none() {
+                    visited.insert(*key, i as u64 + 1);
+                }
+            }
+        }
+    }
+}
+
 
 fn run(name: &str, f: impl FnOnce(&str) -> u64, input: &str) {
     let t0 = std::time::Instant::now();
@@ -178,6 +256,9 @@ pub fn main() {
     run("part_2", part_2, include_str!("d14-test.txt"));
     run("part_2", part_2, include_str!("d14-prod.txt"));
 
+    run("part_2_table", part_2_table, include_str!("d14-test.txt"));
+    run("part_2_table", part_2_table, include_str!("d14-prod.txt"));
+
     println!();
 }
 